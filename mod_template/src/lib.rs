@@ -12,14 +12,19 @@
 /// #[mod_template::define(
 ///     macro_rules! define_foo_mod;
 ///     constructions(BAR -> impl crate::BarTrait),
-///     attribute_substitutions(BAZ)),
-/// ]
+///     attribute_substitutions(BAZ),
+///     types(Qux: crate::QuxTrait),
+/// )]
 /// mod __ {
 ///     #[__CONSTRUCT(bar as BAR)]
 ///     #[__SUBSTITUTE(BAZ)]
 ///     fn an_fn() {
 ///         bar.do_something();
 ///     }
+///
+///     fn another_fn(qux: Qux) {
+///         qux.do_something_else();
+///     }
 /// }
 ///
 /// // Call the defined macro to produce an actual module (named as `actual_foo`):
@@ -34,20 +39,482 @@
 ///         // `#[__SUBSTITUTE(BAZ)]`, use
 ///         // `BAZ => #[::baz::baz] (.., qux: crate::Qux),`.
 ///     },
+///     types {
+///         Qux => crate::Qux,
+///     },
+/// }
+/// ```
+///
+/// # Tables
+///
+/// Instead of the header, the macro call can start with blocks shared by the
+/// modules listed in a `mods` block, each with its own header and blocks. The
+/// definitions in the shared blocks are defaults, which a module can replace
+/// by defining the same target names in its own blocks:
+///
+/// ```ignore
+/// define_sumer_test_suite! {
+///     constructions { SUMER => Box::new(crate::BuiltinSumer) },
+///     attribute_substitutions { TEST => #[test] },
+///     mods {
+///         mod imperative_sumer_test_suite => {
+///             constructions { SUMER => Box::new(crate::ImperativeSumer) },
+///         },
+///         pub mod builtin_sumer_test_suite => {},
+///     },
+/// }
+/// ```
+///
+/// # Matrices
+///
+/// A `matrix` block in the macro call defines one module per combination of
+/// alternatives, one alternative from each axis. Each alternative has a label
+/// and blocks that are merged with the blocks outside the `matrix` block. The
+/// name of each module (or function, for a template of a function) is the name
+/// in the header suffixed with the labels of the chosen alternatives, in order.
+/// Instances of a template of an `impl` block have no name to be suffixed, so
+/// they can't have a `matrix` block:
+///
+/// ```ignore
+/// // Defines `codec_test_suite_big_endian_zero`,
+/// // `codec_test_suite_big_endian_max`, `codec_test_suite_little_endian_zero`
+/// // and `codec_test_suite_little_endian_max`.
+/// define_codec_test_suite! {
+///     mod codec_test_suite;
+///     attribute_substitutions { TEST => #[test] },
+///     matrix {
+///         codec {
+///             big_endian => { constructions { CODEC => crate::BigEndian } },
+///             little_endian => { constructions { CODEC => crate::LittleEndian } },
+///         },
+///         number {
+///             zero => { constructions { NUMBER => 0 } },
+///             max => { constructions { NUMBER => u32::MAX } },
+///         },
+///     },
+/// }
+/// ```
+///
+/// # Constructions of `impl` types
+///
+/// A construction can be declared with an `impl` type (e.g.
+/// `constructions(STORE -> impl crate::Store)`), so that each actual module
+/// can construct a value of its own type. In the compiler check dummy module,
+/// the body of a function using such a construction is type-checked in a copy
+/// of the function that takes the value as an argument, while the function
+/// itself keeps its signature, so other functions in the template can still
+/// call it. Thus, such a construction can't be used in functions returning
+/// `impl` types, or in methods of traits or trait implementations.
+///
+/// # Default constructions
+///
+/// A construction can be declared with a default expression (e.g.
+/// `constructions(CLOCK -> crate::Clock = crate::Clock::system())`), which is
+/// used when the construction is omitted in the macro call. In the compiler
+/// check dummy module, the default expression is type-checked once, as the body
+/// of a function returning the declared type, which takes the parameters
+/// declared in the parameter list extensions of the attribute substitutions
+/// (see below) as its arguments.
+///
+/// # Default attribute substitutions
+///
+/// Likewise, an attribute substitution can be declared with default attributes
+/// (e.g. `attribute_substitutions(TEST = #[test])`), which are used when the
+/// attribute substitution is omitted in the macro call. An attribute
+/// substitution declared as optional (e.g.
+/// `attribute_substitutions(TRACE?)`) substitutes no attributes when omitted.
+///
+/// # Declared parameter list extensions
+///
+/// An attribute substitution can also be declared with a parameter list
+/// extension (e.g. `attribute_substitutions(TEST(.., pool: Pool<Sqlite>))`),
+/// so that the functions annotated by `#[__SUBSTITUTE(TEST)]` and the
+/// constructions used in them can rely on those parameters. The extension in
+/// the macro call can be omitted, in which case the declared one is used, or
+/// else it should start with the declared parameters, optionally followed by
+/// extra ones.
+///
+/// Constructions and parameter list extensions in the macro call are resolved
+/// at the template, so that they can refer to each other, as well as to the
+/// declared parameters, despite hygiene.
+///
+/// # Field list extensions
+///
+/// An attribute substitution in the macro call can also extend the field list
+/// of the structs annotated by `#[__SUBSTITUTE(..)]`, with a braced list of
+/// fields after the new attributes (e.g.
+/// `TARGET => #[derive(Debug)] { extra: Foo }`, or `TARGET => { pub Foo }` for
+/// tuple structs), so that each actual module can add its own fields, e.g. a
+/// handle to a specific backend. The fields are appended, and the template
+/// can't rely on them, since they aren't declared.
+///
+/// # Types
+///
+/// Types declared in the `types` block can be named anywhere in the template.
+/// In actual modules, they are type aliases of the types specified in the
+/// macro call, which are checked against the declared bounds.
+///
+/// In the compiler check dummy module, a function that mentions a declared type
+/// is made generic over that type, so that the template is type-checked against
+/// the declared bounds. Likewise, structs, enums, unions, type aliases and
+/// traits that mention a declared type are made generic over it, along with
+/// their `impl` blocks and the functions mentioning them, and paths to all of
+/// those get the type as a generic argument (e.g. `make()` becomes
+/// `make::<Backend>()`), so their users don't rely on type inference. Test
+/// attributes (e.g. `#[test]`) are removed from the functions made generic
+/// there, since tests can't be generic. Methods are made generic as well, but
+/// calls to them are left alone, so they should let the compiler infer the type
+/// (e.g. from the arguments). Also, paths inside attributes, and inside macro
+/// calls whose arguments aren't expressions, are left alone.
+///
+/// Names are resolved through the modules and blocks of the template, along
+/// with their `use` declarations, glob imports included, so items of the same
+/// names in different modules are told apart. Items named like a declared type
+/// shadow it in their modules, in actual modules and in the compiler check
+/// dummy module alike.
+///
+/// # Consts
+///
+/// Consts declared in the `consts` block (e.g. `consts(MAX_ITEMS: usize)`)
+/// become actual `const` items in the module and its inline submodules, with
+/// the values specified in the macro call (e.g. `consts { MAX_ITEMS => 64 }`).
+/// Thus, unlike constructions, they can be used in array lengths, const
+/// generics and module-level items.
+///
+/// Since const items are always evaluated, in the compiler check dummy module,
+/// declared consts of primitive types (integers, floats, `bool`, `char` and
+/// `&str`) are const items with placeholder values: zeros, `false`, `'\0'`
+/// and empty strings. Thus, the template shouldn't assume they are non-zero in
+/// constant evaluations (e.g. dividing by them). Declared consts of other
+/// types (e.g. structs of the crate) are bound to `unreachable!()`-style
+/// placeholder values at the beginning of the bodies of the functions using
+/// them instead, like constructions, so they can only be used in function
+/// bodies there.
+///
+/// # Identifiers
+///
+/// Identifiers declared in the `idents` block (e.g. `idents(GET_ANSWER)`) are
+/// replaced by the identifiers specified in the macro call (e.g.
+/// `idents { GET_ANSWER => get_positive_answer }`), so that items exported to
+/// a flat namespace (e.g. `#[no_mangle]` functions) can have different names
+/// in different modules. Only the explicitly marked uses are replaced:
+///
+/// - `__IDENT(GET_ANSWER)`, wherever a function call can be parsed, e.g. in
+///   expressions, types, patterns and the arguments of attributes.
+/// - The name of an item with the attribute `#[__IDENT(GET_ANSWER)]`, e.g.
+///   `#[__IDENT(GET_ANSWER)] pub extern "C" fn get_answer() -> i32 { .. }`.
+///
+/// The names in the `idents` block can't be declared in the `constructions`,
+/// `types` or `consts` blocks as well.
+///
+/// In the compiler check dummy module, they are replaced by placeholder names
+/// prefixed with the name of the defined macro.
+///
+/// # Extra items
+///
+/// Items in the `items` block of the macro call (e.g.
+/// `items { #[test] fn another_test() { ... } }`) are appended to the actual
+/// module as is, so they can use the imports and private items of the
+/// template. Helper attributes like `#[__CONSTRUCT(..)]` are not processed in
+/// them.
+///
+/// # Skipping items
+///
+/// Top-level items of the template named in the `skip` block of the macro call
+/// (e.g. `skip { it_works_in_transaction }`) are left out of the actual module.
+/// It's an error to name an item that doesn't exist in the template.
+///
+/// # Overriding functions
+///
+/// Functions in the `overrides` block of the macro call (e.g.
+/// `overrides { fn it_greets() { ... } }`) replace the bodies of the top-level
+/// functions with the same names in the template. Only top-level functions can
+/// be overridden, not methods or functions in nested modules. Each function can
+/// be overridden only once. Their signatures must match the ones in the
+/// template, ignoring trailing commas, and they can't have attributes, since the
+/// attributes in the template (e.g. `#[__CONSTRUCT(..)]`) are kept and
+/// processed as usual. Thus, the variables bound by those attributes can be
+/// used in the new bodies.
+///
+/// # Extending templates
+///
+/// A template can extend another one with the `extends` option, naming the
+/// macro defined by the latter:
+///
+/// ```ignore
+/// #[mod_template::define(
+///     macro_rules! define_tx_store_test_suite;
+///     extends(define_store_test_suite),
+///     constructions(TX_STORE -> impl crate::TransactionalStore),
+/// )]
+/// mod __ {
+///     #[__CONSTRUCT(mut store as TX_STORE)]
+///     #[__SUBSTITUTE(TEST)]
+///     fn rollback_discards_changes() { ... }
+/// }
+/// ```
+///
+/// An actual module defined by the macro of the extending template contains the
+/// items of both templates, and the macro call should define everything
+/// declared by both. The names declared by the extending template must not
+/// collide with the inherited ones. The macro of the extended template must be
+/// in scope wherever the macro of the extending template is called.
+///
+/// The compiler check dummy module of an extending template is made by the
+/// macro of the extended one, with the declarations and the items of both.
+///
+/// # Nested instances
+///
+/// A template can instantiate another template by calling its macro marked with
+/// `#[__NESTED]`. In the `constructions` and `attribute_substitutions` blocks of
+/// that call, `__FORWARD(NAME)` is replaced with what the outer macro call
+/// defines for `NAME`:
+///
+/// ```ignore
+/// #[mod_template::define(
+///     macro_rules! define_greeter_test_suite;
+///     constructions(GREETER -> Box<dyn crate::Greeter>),
+///     attribute_substitutions(TEST),
+/// )]
+/// mod __ {
+///     #[__NESTED]
+///     define_smoke_test_suite! {
+///         pub mod smoke;
+///         constructions { SUBJECT => __FORWARD(GREETER) },
+///         attribute_substitutions { TEST => __FORWARD(TEST) },
+///     }
+/// }
+/// ```
+///
+/// Other than that, the marked call is kept as is, so helper attributes in it
+/// are left for the nested template. Types and consts of the outer template can
+/// be referred to with `super::` instead. Nested instances are left out of the
+/// compiler check of the template.
+///
+/// # Local macros
+///
+/// The template can contain its own `macro_rules!`. The `$` tokens in the
+/// template are escaped in the defined macro, so they are kept as is in the
+/// actual modules. In that case, the defined macro calls itself by its name,
+/// so it must be in scope by its name wherever it's called, unless it has a
+/// `#[macro_export]` header, in which case it calls itself by its path in the
+/// crate defining it.
+///
+/// # Cross-crate templates
+///
+/// With a `#[macro_export]` header, the defined macro can be called from other
+/// crates. Paths starting with `__CRATE` in the template and in the options of
+/// this attribute refer to the crate defining the template. They become
+/// `$crate` in the defined macro, and `crate` in the compiler check:
+///
+/// ```ignore
+/// #[mod_template::define(
+///     #[macro_export]
+///     macro_rules! define_sumer_test_suite;
+///     constructions(SUMER -> Box<dyn __CRATE::Sumer>),
+/// )]
+/// mod __ {
+///     use __CRATE::Sumer;
+///     // ...
 /// }
 /// ```
+///
+/// The crates calling the defined macro should depend on `mod_template` as
+/// well, unless the `crate` option below refers to a re-export of it.
+///
+/// # Path to this crate
+///
+/// The code generated by the defined macro refers to this crate by
+/// `::mod_template` by default. If this crate is re-exported by another one,
+/// the path can be changed with the `crate` option, similar to
+/// `#[serde(crate = "...")]`:
+///
+/// ```ignore
+/// #[mod_template::define(
+///     #[macro_export]
+///     macro_rules! define_sumer_test_suite;
+///     crate = __CRATE::mod_template,
+///     constructions(SUMER -> Box<dyn __CRATE::Sumer>),
+/// )]
+/// mod __ { ... }
+/// ```
+///
+/// # Modules in files
+///
+/// The content of a submodule of the template can be loaded from a file when
+/// the template is defined, so that it's processed like an inline module:
+///
+/// ```ignore
+/// #[mod_template::define(macro_rules! define_sumer_test_suite; ...)]
+/// mod __ {
+///     #[__FROM_FILE]
+///     mod edge_cases {}
+///     #[__FROM_FILE("path/to/other_cases.rs")]
+///     mod other_cases {}
+/// }
+/// ```
+///
+/// Paths are resolved like the ones of `mod edge_cases;` by rustc, as if the
+/// template were the module of the file it's defined in: `edge_cases` is
+/// loaded from `edge_cases.rs` or `edge_cases/mod.rs` next to that file if the
+/// latter is a `mod.rs` or the root of the crate, or else from the directory
+/// named after it (e.g. `foo/edge_cases.rs` for `foo.rs`), and
+/// `#[__FROM_FILE("...")]` takes a path relative to that file. Modules in a
+/// loaded file can use `#[__FROM_FILE]` too, and a file loaded from an explicit
+/// path is treated like a `mod.rs`. A plain `mod edge_cases;` can't be used as
+/// stable Rust doesn't allow it in the input of attribute macros.
+///
+/// The whole content of the template can also be loaded from a file with the
+/// `path` option, relative to the file where the template is defined:
+///
+/// ```ignore
+/// #[mod_template::define(
+///     macro_rules! define_sumer_test_suite;
+///     path = "templates/sumer.rs",
+///     constructions(SUMER -> Box<dyn crate::Sumer>),
+/// )]
+/// mod __ {}
+/// ```
+///
+/// Like a module with the attribute `#[path = "..."]`, modules in that file
+/// are loaded relative to the directory of that file. As there is no way to
+/// point into another file on stable, errors in a loaded file are reported at
+/// the path that loads it. Changes to loaded files trigger a recompilation of
+/// the template.
+///
+/// # Templates of functions
+///
+/// A template can also be a single function named `__`, with the helper
+/// attributes put on the function itself. Each call of the defined macro then
+/// defines a function named after its header:
+///
+/// ```ignore
+/// #[mod_template::define(
+///     macro_rules! define_sum_test;
+///     constructions(SUMER -> Box<dyn crate::Sumer>),
+///     attribute_substitutions(TEST),
+/// )]
+/// #[__CONSTRUCT(sumer as SUMER)]
+/// #[__SUBSTITUTE(TEST)]
+/// fn __() {
+///     assert_eq!(sumer.sum(&[1, 2]), 3);
+/// }
+///
+/// define_sum_test! {
+///     fn imperative_sumer_sums;
+///     constructions { SUMER => Box::new(crate::ImperativeSumer) },
+///     attribute_substitutions { TEST => #[test] },
+/// }
+/// ```
+///
+/// Attributes in the header come before those of the function in the
+/// template. As there is no module around the function, the types and consts
+/// declared in the `types` and `consts` blocks can only be used in its body.
+/// Such a template can't extend or be extended by other templates, nor can it
+/// be loaded by the `path` option, and the `items`, `skip` and `overrides`
+/// blocks don't apply to it.
+///
+/// # Templates of `impl` blocks
+///
+/// Similarly, a template can be an `impl` block for `__`, which each call of
+/// the defined macro implements for the type in its header:
+///
+/// ```ignore
+/// #[mod_template::define(
+///     macro_rules! impl_unit;
+///     constructions(SYMBOL -> &'static str),
+/// )]
+/// impl crate::Unit for __ {
+///     #[__CONSTRUCT(symbol as SYMBOL)]
+///     fn symbol(&self) -> &'static str {
+///         symbol
+///     }
+/// }
+///
+/// impl_unit! {
+///     impl<T> crate::Celsius<T> where T: Copy;
+///     constructions { SYMBOL => "°C" },
+/// }
+/// ```
+///
+/// The generics in the header come before those in the template. The same
+/// restrictions as templates of functions apply, so the declared types and
+/// consts can only be used in the bodies of the methods, not in their
+/// signatures. The compiler check of the template implements for a
+/// placeholder struct without fields, so the methods can't rely on anything
+/// specific to the actual types, and reading a field of `self`, e.g.
+/// `self.name` or `self.0`, is an error. Call methods of a trait implemented
+/// by the actual types instead. For a trait, the placeholder struct is assumed
+/// to implement its supertraits.
+///
+/// # Inline instances
+///
+/// With `mod _;` as the header, the items of a template of a module are put
+/// directly into the scope of the macro call, without a wrapping module:
+///
+/// ```ignore
+/// define_sumer_test_suite! {
+///     mod _;
+///     constructions { SUMER => Box::new(crate::ImperativeSumer) },
+///     attribute_substitutions { TEST => #[test] },
+/// }
+/// ```
+///
+/// Such an instance can't have attributes or a visibility. Inlining items with
+/// the same names into the same scope more than once, e.g. by inlining the same
+/// template twice, fails to compile, and the headers of the instances are
+/// reported along with the colliding items. The `idents` block can be used to
+/// give the items different names.
+///
+/// The types and consts declared in the `types` and `consts` blocks aren't put
+/// into the scope of the macro call, but into the bodies of the functions and
+/// methods, and into the nested modules, of such an instance. So they can't be
+/// used elsewhere in it, e.g. in the signatures of functions or in statics.
+///
+/// # Instances in functions
+///
+/// A template can be instantiated inside a function body or any other block.
+/// With `mod _;`, the items become items of the block, so the constructions,
+/// types and consts can name the items declared in the block:
+///
+/// ```ignore
+/// fn sumers() {
+///     struct LocalSumer;
+///     impl crate::Sumer for LocalSumer { /* ... */ }
+///
+///     define_sumer_test_suite! {
+///         mod _;
+///         constructions { SUMER => Box::new(LocalSumer) },
+///     }
+///
+///     // Call the generated functions of the suite here.
+/// }
+/// ```
+///
+/// The generated items are still items, not closures, so the same rules as for
+/// any nested item apply: local variables of the function can't be used, and a
+/// named instance like `mod local_suite;` is a module, where the items of the
+/// block aren't visible, so it can only name items outside the block, e.g. with
+/// `crate::` paths. Also, `#[test]` has no effect on items inside a function,
+/// so call the generated functions instead.
 pub use mod_template_macros::define;
 
 /// This attribute is used by the attribute `mod_template::define` internally.
 /// One should not use this attribute manually.
 pub use mod_template_macros::__monomorphize_mod;
 
+/// This attribute is used by the attribute `mod_template::define` internally.
+/// One should not use this attribute manually.
+pub use mod_template_macros::__compiler_check_dummy;
+
 /// Turns something like:
 ///
 /// ```
-/// #[mod_template::construct(one = 1, mut to_be_three: i32 = 2)]
-/// #[test]
-/// fn test_one_adds_three() {
+/// #[mod_template::construct(
+///     one = 1, mut to_be_three: i32 = 2,
+///     four_text: impl std::fmt::Display = "4",
+/// )]
+/// fn assert_one_adds_three() {
 ///     to_be_three += 1;
 ///     assert_eq!(format!("{}", one + to_be_three), four_text.to_string())
 /// }
@@ -56,13 +523,12 @@ pub use mod_template_macros::__monomorphize_mod;
 /// into:
 ///
 /// ```no_run
-/// #[test]
-/// fn test_one_adds_three() {
+/// fn assert_one_adds_three() {
 ///     let one = 1;
 ///     let mut to_be_three: i32 = 2;
 ///     let four_text = {
-///         fn type_checked() -> impl std::fmt::Display { "4" }
-///         type_checked()
+///         fn type_checked(v: impl std::fmt::Display) -> impl std::fmt::Display { v }
+///         type_checked("4")
 ///     };
 ///     to_be_three += 1;
 ///     assert_eq!(format!("{}", one + to_be_three), four_text.to_string())
@@ -89,3 +555,27 @@ pub use mod_template_macros::construct;
 /// }
 /// ```
 pub use mod_template_macros::extend_parameter_list;
+
+/// Turns something like:
+///
+/// ```
+/// #[mod_template::extend_field_list(.., pub handle: u32)]
+/// #[derive(Debug)]
+/// struct Connection {
+///     name: String,
+/// }
+/// ```
+///
+/// into:
+///
+/// ```no_run
+/// #[derive(Debug)]
+/// struct Connection {
+///     name: String,
+///     pub handle: u32,
+/// }
+/// ```
+///
+/// Tuple structs are extended likewise, e.g. with
+/// `#[mod_template::extend_field_list(.., pub u32)]`.
+pub use mod_template_macros::extend_field_list;
@@ -2,12 +2,13 @@ use std::collections::HashSet;
 
 use syn::parse::Parse;
 
-use crate::attributes::extend_parameter_list;
+use crate::attributes::{extend_field_list, extend_parameter_list};
 
 pub struct AttributeSubstitutionDefinition {
     target_name_ident: syn::Ident,
     new_attributes: Vec<syn::Attribute>,
     parameter_list_extension: Option<extend_parameter_list::AttributeOptions>,
+    field_list_extension: Option<extend_field_list::AttributeOptions>,
 }
 
 impl AttributeSubstitutionDefinition {
@@ -22,6 +23,10 @@ impl AttributeSubstitutionDefinition {
     pub fn parameter_list_extension(&self) -> &Option<extend_parameter_list::AttributeOptions> {
         &self.parameter_list_extension
     }
+
+    pub fn field_list_extension(&self) -> &Option<extend_field_list::AttributeOptions> {
+        &self.field_list_extension
+    }
 }
 
 pub fn parse(input: syn::parse::ParseStream) -> syn::Result<Vec<AttributeSubstitutionDefinition>> {
@@ -36,8 +41,18 @@ pub fn parse(input: syn::parse::ParseStream) -> syn::Result<Vec<AttributeSubstit
         } else {
             None
         };
+        let field_list_extension = if input.peek(syn::token::Brace) {
+            let content;
+            syn::braced!(content in input);
+            Some(content.call(extend_field_list::AttributeOptions::parse_field_list)?)
+        } else {
+            None
+        };
 
-        if new_attributes.is_empty() && parameter_list_extension.is_none() {
+        if new_attributes.is_empty()
+            && parameter_list_extension.is_none()
+            && field_list_extension.is_none()
+        {
             return Err(syn::Error::new(
                 target_name_ident.span(),
                 format!(
@@ -51,11 +66,14 @@ pub fn parse(input: syn::parse::ParseStream) -> syn::Result<Vec<AttributeSubstit
 
         let parameter_list_extension =
             parameter_list_extension.and_then(|x| if x.is_noop() { None } else { Some(x) });
+        let field_list_extension =
+            field_list_extension.and_then(|x| if x.is_noop() { None } else { Some(x) });
 
         Ok(AttributeSubstitutionDefinition {
             target_name_ident,
             new_attributes,
             parameter_list_extension,
+            field_list_extension,
         })
     }
 
@@ -95,7 +113,7 @@ pub(crate) mod tests {
     use quote::ToTokens;
     use syn::parse::Parser;
 
-    use crate::attributes::extend_parameter_list;
+    use crate::attributes::{extend_field_list, extend_parameter_list};
 
     use super::{parse, AttributeSubstitutionDefinition};
 
@@ -104,6 +122,8 @@ pub(crate) mod tests {
         target_name: String,
         new_attributes: Vec<String>,
         parameter_list_extension: Option<extend_parameter_list::tests::AttributeOptionsForTest>,
+        #[builder(default)]
+        field_list_extension: Option<extend_field_list::tests::AttributeOptionsForTest>,
     }
     impl From<AttributeSubstitutionDefinition> for AttributeSubstitutionDefinitionForTest {
         fn from(value: AttributeSubstitutionDefinition) -> Self {
@@ -111,6 +131,7 @@ pub(crate) mod tests {
                 target_name_ident,
                 new_attributes,
                 parameter_list_extension,
+                field_list_extension,
             } = value;
 
             Self {
@@ -120,6 +141,7 @@ pub(crate) mod tests {
                     .map(|attr| attr.into_token_stream().to_string())
                     .collect(),
                 parameter_list_extension: parameter_list_extension.map(|x| x.into()),
+                field_list_extension: field_list_extension.map(|x| x.into()),
             }
         }
     }
@@ -129,12 +151,15 @@ pub(crate) mod tests {
         let attr_foo = quote::quote!(#[foo]);
         let attr_bar = quote::quote!(#[bar]);
         let param_list_baz = quote::quote!(baz_1: Baz, mut baz_2: &mut Baz);
+        let field_list_qux = quote::quote!(pub qux: Qux);
 
         let input = quote::quote!({
             WITH_ATTRS => #attr_foo #attr_bar,
             WITH_EXT => (.., #param_list_baz),
             WITH_ATTRS_EXT => #attr_foo #attr_bar (.., #param_list_baz),
             WITH_EMPTY_EXT => (..),
+            WITH_FIELDS => #attr_foo { #field_list_qux },
+            WITH_EMPTY_FIELDS => {},
         });
 
         let expected = vec![
@@ -168,6 +193,22 @@ pub(crate) mod tests {
                 .new_attributes(vec![])
                 .parameter_list_extension(None)
                 .build(),
+            AttributeSubstitutionDefinitionForTest::builder()
+                .target_name("WITH_FIELDS".to_string())
+                .new_attributes(vec![attr_foo.to_string()])
+                .parameter_list_extension(None)
+                .field_list_extension(Some(
+                    extend_field_list::tests::AttributeOptionsForTest::builder()
+                        .direction(extend_field_list::Direction::Append)
+                        .field_list(field_list_qux.to_string())
+                        .build(),
+                ))
+                .build(),
+            AttributeSubstitutionDefinitionForTest::builder()
+                .target_name("WITH_EMPTY_FIELDS".to_string())
+                .new_attributes(vec![])
+                .parameter_list_extension(None)
+                .build(),
         ];
 
         let actual = (|input: syn::parse::ParseStream| parse(input))
@@ -0,0 +1,110 @@
+use std::collections::HashSet;
+
+pub struct ConstDefinition {
+    target_name_ident: syn::Ident,
+    value: syn::Expr,
+}
+
+impl ConstDefinition {
+    pub fn target_name_ident(&self) -> &syn::Ident {
+        &self.target_name_ident
+    }
+
+    pub fn value(&self) -> &syn::Expr {
+        &self.value
+    }
+}
+
+pub fn parse(input: syn::parse::ParseStream) -> syn::Result<Vec<ConstDefinition>> {
+    fn parse_param(input: syn::parse::ParseStream) -> syn::Result<ConstDefinition> {
+        let target_name_ident: syn::Ident = input.parse()?;
+        let _: syn::Token![=>] = input.parse()?;
+        let value: syn::Expr = input.parse()?;
+
+        Ok(ConstDefinition {
+            target_name_ident,
+            value,
+        })
+    }
+
+    let vec: Vec<_> = {
+        let content;
+        syn::braced!(content in input);
+        content
+            .parse_terminated(parse_param, syn::Token![,])?
+            .into_iter()
+            .collect()
+    };
+
+    {
+        let mut previous_names = HashSet::new();
+        for ConstDefinition {
+            target_name_ident, ..
+        } in &vec
+        {
+            let target_name = target_name_ident.to_string();
+            if previous_names.contains(&target_name) {
+                return Err(syn::Error::new(
+                    target_name_ident.span(),
+                    "duplicate target name",
+                ));
+            }
+            previous_names.insert(target_name);
+        }
+    }
+
+    Ok(vec)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use quote::ToTokens;
+    use syn::parse::Parser;
+
+    use super::{parse, ConstDefinition};
+
+    #[derive(Debug, PartialEq, Eq, typed_builder::TypedBuilder)]
+    pub struct ConstDefinitionForTest {
+        target_name: String,
+        value: String,
+    }
+    impl From<ConstDefinition> for ConstDefinitionForTest {
+        fn from(value: ConstDefinition) -> Self {
+            let ConstDefinition {
+                target_name_ident,
+                value,
+            } = value;
+
+            Self {
+                target_name: target_name_ident.to_string(),
+                value: value.into_token_stream().to_string(),
+            }
+        }
+    }
+
+    #[test]
+    fn basic() {
+        let expr_foo = quote::quote!(64);
+        let expr_bar = quote::quote!(crate::BAR * 2);
+
+        let input = quote::quote!({ FOO => #expr_foo, BAR => #expr_bar });
+
+        let expected = vec![
+            ConstDefinitionForTest::builder()
+                .target_name("FOO".to_string())
+                .value(expr_foo.to_string())
+                .build(),
+            ConstDefinitionForTest::builder()
+                .target_name("BAR".to_string())
+                .value(expr_bar.to_string())
+                .build(),
+        ];
+
+        let actual = (|input: syn::parse::ParseStream| parse(input))
+            .parse2(input)
+            .expect("should be able to parse the input");
+        let actual: Vec<ConstDefinitionForTest> = actual.into_iter().map(|x| x.into()).collect();
+
+        assert_eq!(actual, expected)
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::HashSet;
+
+pub struct IdentDefinition {
+    target_name_ident: syn::Ident,
+    ident: syn::Ident,
+}
+
+impl IdentDefinition {
+    pub fn target_name_ident(&self) -> &syn::Ident {
+        &self.target_name_ident
+    }
+
+    pub fn ident(&self) -> &syn::Ident {
+        &self.ident
+    }
+}
+
+pub fn parse(input: syn::parse::ParseStream) -> syn::Result<Vec<IdentDefinition>> {
+    fn parse_param(input: syn::parse::ParseStream) -> syn::Result<IdentDefinition> {
+        let target_name_ident: syn::Ident = input.parse()?;
+        let _: syn::Token![=>] = input.parse()?;
+        let ident: syn::Ident = input.parse()?;
+
+        Ok(IdentDefinition {
+            target_name_ident,
+            ident,
+        })
+    }
+
+    let vec: Vec<_> = {
+        let content;
+        syn::braced!(content in input);
+        content
+            .parse_terminated(parse_param, syn::Token![,])?
+            .into_iter()
+            .collect()
+    };
+
+    {
+        let mut previous_names = HashSet::new();
+        for IdentDefinition {
+            target_name_ident, ..
+        } in &vec
+        {
+            let target_name = target_name_ident.to_string();
+            if previous_names.contains(&target_name) {
+                return Err(syn::Error::new(
+                    target_name_ident.span(),
+                    "duplicate target name",
+                ));
+            }
+            previous_names.insert(target_name);
+        }
+    }
+
+    Ok(vec)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use syn::parse::Parser;
+
+    use super::{parse, IdentDefinition};
+
+    #[derive(Debug, PartialEq, Eq, typed_builder::TypedBuilder)]
+    pub struct IdentDefinitionForTest {
+        target_name: String,
+        ident: String,
+    }
+    impl From<IdentDefinition> for IdentDefinitionForTest {
+        fn from(value: IdentDefinition) -> Self {
+            let IdentDefinition {
+                target_name_ident,
+                ident,
+            } = value;
+
+            Self {
+                target_name: target_name_ident.to_string(),
+                ident: ident.to_string(),
+            }
+        }
+    }
+
+    #[test]
+    fn basic() {
+        let input = quote::quote!({ FOO => foo, BAR => r#bar });
+
+        let expected = vec![
+            IdentDefinitionForTest::builder()
+                .target_name("FOO".to_string())
+                .ident("foo".to_string())
+                .build(),
+            IdentDefinitionForTest::builder()
+                .target_name("BAR".to_string())
+                .ident("r#bar".to_string())
+                .build(),
+        ];
+
+        let actual = (|input: syn::parse::ParseStream| parse(input))
+            .parse2(input)
+            .expect("should be able to parse the input");
+        let actual: Vec<IdentDefinitionForTest> = actual.into_iter().map(|x| x.into()).collect();
+
+        assert_eq!(actual, expected)
+    }
+}
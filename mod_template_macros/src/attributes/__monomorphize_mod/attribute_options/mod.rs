@@ -1,9 +1,15 @@
 pub(crate) mod attribute_substitution_declaration;
+pub(crate) mod const_declaration;
 pub(crate) mod construction_declaration;
-mod mod_header;
+pub(crate) mod ident_declaration;
+pub(crate) mod mod_header;
+pub(crate) mod type_declaration;
 
 pub use self::attribute_substitution_declaration::AttributeSubstitutionDefinition;
+pub use self::const_declaration::ConstDefinition;
 pub use self::construction_declaration::ConstructionDefinition;
+pub use self::ident_declaration::IdentDefinition;
+pub use self::type_declaration::TypeDefinition;
 
 use self::mod_header::ModHeader;
 
@@ -11,6 +17,12 @@ pub struct AttributeOptions {
     mod_header: ModHeader,
     constructions: Vec<ConstructionDefinition>,
     attribute_substitutions: Vec<AttributeSubstitutionDefinition>,
+    types: Vec<TypeDefinition>,
+    consts: Vec<ConstDefinition>,
+    idents: Vec<IdentDefinition>,
+    items: Vec<syn::Item>,
+    skip: Vec<syn::Ident>,
+    overrides: Vec<syn::ItemFn>,
 }
 
 impl AttributeOptions {
@@ -23,6 +35,24 @@ impl AttributeOptions {
     pub fn attribute_substitutions(&self) -> &Vec<AttributeSubstitutionDefinition> {
         &self.attribute_substitutions
     }
+    pub fn types(&self) -> &Vec<TypeDefinition> {
+        &self.types
+    }
+    pub fn consts(&self) -> &Vec<ConstDefinition> {
+        &self.consts
+    }
+    pub fn idents(&self) -> &Vec<IdentDefinition> {
+        &self.idents
+    }
+    pub fn items(&self) -> &Vec<syn::Item> {
+        &self.items
+    }
+    pub fn skip(&self) -> &Vec<syn::Ident> {
+        &self.skip
+    }
+    pub fn overrides(&self) -> &Vec<syn::ItemFn> {
+        &self.overrides
+    }
 }
 
 impl syn::parse::Parse for AttributeOptions {
@@ -33,12 +63,24 @@ impl syn::parse::Parse for AttributeOptions {
                 mod_header,
                 constructions: vec![],
                 attribute_substitutions: vec![],
+                types: vec![],
+                consts: vec![],
+                idents: vec![],
+                items: vec![],
+                skip: vec![],
+                overrides: vec![],
             });
         }
         let _: syn::Token![;] = input.parse()?;
 
         let mut constructions: Option<Vec<ConstructionDefinition>> = None;
         let mut attribute_substitutions: Option<Vec<AttributeSubstitutionDefinition>> = None;
+        let mut types: Option<Vec<TypeDefinition>> = None;
+        let mut consts: Option<Vec<ConstDefinition>> = None;
+        let mut idents: Option<Vec<IdentDefinition>> = None;
+        let mut items: Option<Vec<syn::Item>> = None;
+        let mut skip: Option<Vec<syn::Ident>> = None;
+        let mut overrides: Option<Vec<syn::ItemFn>> = None;
 
         loop {
             if input.is_empty() {
@@ -46,6 +88,12 @@ impl syn::parse::Parse for AttributeOptions {
                     mod_header,
                     constructions: constructions.unwrap_or_default(),
                     attribute_substitutions: attribute_substitutions.unwrap_or_default(),
+                    types: types.unwrap_or_default(),
+                    consts: consts.unwrap_or_default(),
+                    idents: idents.unwrap_or_default(),
+                    items: items.unwrap_or_default(),
+                    skip: skip.unwrap_or_default(),
+                    overrides: overrides.unwrap_or_default(),
                 });
             }
 
@@ -72,6 +120,63 @@ impl syn::parse::Parse for AttributeOptions {
                     attribute_substitutions =
                         Some(attribute_substitution_declaration::parse(input)?);
                 }
+                "types" => {
+                    if types.is_some() {
+                        return Err(syn::Error::new(ident.span(), "duplicate types block"));
+                    }
+
+                    types = Some(type_declaration::parse(input)?);
+                }
+                "consts" => {
+                    if consts.is_some() {
+                        return Err(syn::Error::new(ident.span(), "duplicate consts block"));
+                    }
+
+                    consts = Some(const_declaration::parse(input)?);
+                }
+                "idents" => {
+                    if idents.is_some() {
+                        return Err(syn::Error::new(ident.span(), "duplicate idents block"));
+                    }
+
+                    idents = Some(ident_declaration::parse(input)?);
+                }
+                "items" => {
+                    if items.is_some() {
+                        return Err(syn::Error::new(ident.span(), "duplicate items block"));
+                    }
+
+                    let content;
+                    syn::braced!(content in input);
+                    let mut parsed_items = vec![];
+                    while !content.is_empty() {
+                        parsed_items.push(content.parse()?);
+                    }
+                    items = Some(parsed_items);
+                }
+                "skip" => {
+                    if skip.is_some() {
+                        return Err(syn::Error::new(ident.span(), "duplicate skip block"));
+                    }
+
+                    let content;
+                    syn::braced!(content in input);
+                    let names = content.parse_terminated(syn::Ident::parse, syn::Token![,])?;
+                    skip = Some(names.into_iter().collect());
+                }
+                "overrides" => {
+                    if overrides.is_some() {
+                        return Err(syn::Error::new(ident.span(), "duplicate overrides block"));
+                    }
+
+                    let content;
+                    syn::braced!(content in input);
+                    let mut fns = vec![];
+                    while !content.is_empty() {
+                        fns.push(content.parse()?);
+                    }
+                    overrides = Some(fns);
+                }
                 _ => {
                     return Err(syn::Error::new(ident.span(), "unexpected"));
                 }
@@ -93,7 +198,10 @@ pub(crate) mod tests {
 
     use super::{
         attribute_substitution_declaration::tests::AttributeSubstitutionDefinitionForTest,
-        construction_declaration::tests::ConstructionDefinitionForTest, AttributeOptions,
+        const_declaration::tests::ConstDefinitionForTest,
+        construction_declaration::tests::ConstructionDefinitionForTest,
+        ident_declaration::tests::IdentDefinitionForTest,
+        type_declaration::tests::TypeDefinitionForTest, AttributeOptions,
     };
 
     #[derive(Debug, PartialEq, Eq, typed_builder::TypedBuilder)]
@@ -101,6 +209,18 @@ pub(crate) mod tests {
         mod_header: String,
         constructions: Vec<ConstructionDefinitionForTest>,
         attribute_substitutions: Vec<AttributeSubstitutionDefinitionForTest>,
+        #[builder(default)]
+        types: Vec<TypeDefinitionForTest>,
+        #[builder(default)]
+        consts: Vec<ConstDefinitionForTest>,
+        #[builder(default)]
+        idents: Vec<IdentDefinitionForTest>,
+        #[builder(default)]
+        items: Vec<String>,
+        #[builder(default)]
+        skip: Vec<String>,
+        #[builder(default)]
+        overrides: Vec<String>,
     }
 
     impl From<AttributeOptions> for AttributeOptionsForTest {
@@ -109,12 +229,30 @@ pub(crate) mod tests {
                 mod_header,
                 constructions,
                 attribute_substitutions: attr_subst,
+                types,
+                consts,
+                idents,
+                items,
+                skip,
+                overrides,
             } = value;
 
             Self {
                 mod_header: mod_header.into_token_stream().to_string(),
                 constructions: constructions.into_iter().map(|c| c.into()).collect(),
                 attribute_substitutions: attr_subst.into_iter().map(|c| c.into()).collect(),
+                types: types.into_iter().map(|c| c.into()).collect(),
+                consts: consts.into_iter().map(|c| c.into()).collect(),
+                idents: idents.into_iter().map(|c| c.into()).collect(),
+                items: items
+                    .into_iter()
+                    .map(|c| c.into_token_stream().to_string())
+                    .collect(),
+                skip: skip.into_iter().map(|c| c.to_string()).collect(),
+                overrides: overrides
+                    .into_iter()
+                    .map(|c| c.into_token_stream().to_string())
+                    .collect(),
             }
         }
     }
@@ -237,4 +375,73 @@ pub(crate) mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn items() {
+        let mod_header = fixture_mod_header();
+        let item_foo = quote::quote!(
+            #[test]
+            fn foo() {}
+        );
+        let item_bar = quote::quote!(
+            use super::Bar;
+        );
+
+        let input = quote::quote!(#mod_header; items { #item_foo #item_bar });
+
+        let expected = AttributeOptionsForTest::builder()
+            .mod_header(mod_header.to_string())
+            .constructions(vec![])
+            .attribute_substitutions(vec![])
+            .items(vec![item_foo.to_string(), item_bar.to_string()])
+            .build();
+
+        let actual: AttributeOptions = syn::parse2(input).unwrap();
+        let actual: AttributeOptionsForTest = actual.into();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn skip() {
+        let mod_header = fixture_mod_header();
+
+        let input = quote::quote!(#mod_header; skip { foo, bar, });
+
+        let expected = AttributeOptionsForTest::builder()
+            .mod_header(mod_header.to_string())
+            .constructions(vec![])
+            .attribute_substitutions(vec![])
+            .skip(vec!["foo".to_string(), "bar".to_string()])
+            .build();
+
+        let actual: AttributeOptions = syn::parse2(input).unwrap();
+        let actual: AttributeOptionsForTest = actual.into();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn overrides() {
+        let mod_header = fixture_mod_header();
+        let fn_foo = quote::quote!(
+            fn foo() -> i32 {
+                42
+            }
+        );
+
+        let input = quote::quote!(#mod_header; overrides { #fn_foo });
+
+        let expected = AttributeOptionsForTest::builder()
+            .mod_header(mod_header.to_string())
+            .constructions(vec![])
+            .attribute_substitutions(vec![])
+            .overrides(vec![fn_foo.to_string()])
+            .build();
+
+        let actual: AttributeOptions = syn::parse2(input).unwrap();
+        let actual: AttributeOptionsForTest = actual.into();
+
+        assert_eq!(actual, expected);
+    }
 }
@@ -1,6 +1,8 @@
 use proc_macro2::TokenStream;
 use syn::ext::IdentExt;
 
+use crate::attributes::define::TemplateKind;
+
 /// Taken partially from [syn::ItemMod].
 ///
 /// LICENSE: <https://github.com/dtolnay/syn/blob/master/LICENSE-MIT>.
@@ -8,8 +10,28 @@ pub struct ModHeader {
     pub attrs: Vec<syn::Attribute>,
     pub vis: syn::Visibility,
     pub unsafety: Option<syn::Token![unsafe]>,
-    pub mod_token: syn::Token![mod],
-    pub ident: syn::Ident,
+    pub mod_token: ModToken,
+}
+
+/// `fn` is for templates of functions, whose instances are functions named
+/// after the header instead of modules. `impl` is for templates of `impl`
+/// blocks, whose instances implement for the type in the header. `mod _` is
+/// for instances whose items are put directly into the scope of the macro call.
+pub enum ModToken {
+    Mod(syn::Token![mod], syn::Ident),
+    Inline(syn::Token![mod], syn::Token![_]),
+    Fn(syn::Token![fn], syn::Ident),
+    Impl(syn::Token![impl], syn::Generics, Box<syn::Type>),
+}
+
+impl ModHeader {
+    pub fn kind(&self) -> TemplateKind {
+        match self.mod_token {
+            ModToken::Mod(..) | ModToken::Inline(..) => TemplateKind::Mod,
+            ModToken::Fn(..) => TemplateKind::Fn,
+            ModToken::Impl(..) => TemplateKind::Impl,
+        }
+    }
 }
 
 impl syn::parse::Parse for ModHeader {
@@ -17,20 +39,62 @@ impl syn::parse::Parse for ModHeader {
     ///
     /// LICENSE: <https://github.com/dtolnay/syn/blob/master/LICENSE-MIT>.
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        Ok(Self {
-            attrs: input.call(syn::Attribute::parse_outer)?,
-            vis: input.parse()?,
-            unsafety: input.parse()?,
-            mod_token: input.parse()?,
-            ident: if input.peek(syn::Token![try]) {
-                input.call(syn::Ident::parse_any)?
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let vis: syn::Visibility = input.parse()?;
+        let unsafety: Option<syn::Token![unsafe]> = input.parse()?;
+        let mod_token = if input.peek(syn::Token![mod]) && input.peek2(syn::Token![_]) {
+            let mod_token: syn::Token![mod] = input.parse()?;
+            let is_plain =
+                attrs.is_empty() && matches!(vis, syn::Visibility::Inherited) && unsafety.is_none();
+            if !is_plain {
+                return Err(syn::Error::new(
+                    mod_token.span,
+                    "`mod _` can't have attributes, a visibility or `unsafe`, as there is no module",
+                ));
+            }
+            ModToken::Inline(mod_token, input.parse()?)
+        } else if input.peek(syn::Token![mod]) {
+            ModToken::Mod(input.parse()?, parse_name_ident(input)?)
+        } else {
+            if let Some(unsafety) = unsafety {
+                return Err(syn::Error::new(
+                    unsafety.span,
+                    "the qualifiers of a function or an `impl` block come from the template",
+                ));
+            }
+            if input.peek(syn::Token![fn]) {
+                ModToken::Fn(input.parse()?, parse_name_ident(input)?)
             } else {
-                input.parse()?
-            },
+                let impl_token: syn::Token![impl] = input.parse()?;
+                if !matches!(vis, syn::Visibility::Inherited) {
+                    return Err(syn::Error::new(
+                        impl_token.span,
+                        "an `impl` block can't have a visibility",
+                    ));
+                }
+                let mut generics: syn::Generics = input.parse()?;
+                let self_ty = input.parse()?;
+                generics.where_clause = input.parse()?;
+                ModToken::Impl(impl_token, generics, self_ty)
+            }
+        };
+        Ok(Self {
+            attrs,
+            vis,
+            unsafety,
+            mod_token,
         })
     }
 }
 
+fn parse_name_ident(input: syn::parse::ParseStream) -> syn::Result<syn::Ident> {
+    if input.peek(syn::Token![try]) {
+        input.call(syn::Ident::parse_any)
+    } else {
+        input.parse()
+    }
+}
+
 impl quote::ToTokens for ModHeader {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let Self {
@@ -38,7 +102,6 @@ impl quote::ToTokens for ModHeader {
             vis,
             unsafety,
             mod_token,
-            ident,
         } = self;
 
         for attr in attrs {
@@ -46,7 +109,25 @@ impl quote::ToTokens for ModHeader {
         }
         tokens.extend(vis.to_token_stream());
         tokens.extend(unsafety.to_token_stream());
-        tokens.extend(mod_token.to_token_stream());
-        tokens.extend(ident.to_token_stream());
+        match mod_token {
+            ModToken::Mod(mod_token, ident) => {
+                tokens.extend(mod_token.to_token_stream());
+                tokens.extend(ident.to_token_stream());
+            }
+            ModToken::Inline(mod_token, underscore_token) => {
+                tokens.extend(mod_token.to_token_stream());
+                tokens.extend(underscore_token.to_token_stream());
+            }
+            ModToken::Fn(fn_token, ident) => {
+                tokens.extend(fn_token.to_token_stream());
+                tokens.extend(ident.to_token_stream());
+            }
+            ModToken::Impl(impl_token, generics, self_ty) => {
+                tokens.extend(impl_token.to_token_stream());
+                tokens.extend(generics.to_token_stream());
+                tokens.extend(self_ty.to_token_stream());
+                tokens.extend(generics.where_clause.to_token_stream());
+            }
+        }
     }
 }
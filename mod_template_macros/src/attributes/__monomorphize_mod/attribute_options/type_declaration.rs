@@ -0,0 +1,110 @@
+use std::collections::HashSet;
+
+pub struct TypeDefinition {
+    target_name_ident: syn::Ident,
+    ty: syn::Type,
+}
+
+impl TypeDefinition {
+    pub fn target_name_ident(&self) -> &syn::Ident {
+        &self.target_name_ident
+    }
+
+    pub fn ty(&self) -> &syn::Type {
+        &self.ty
+    }
+}
+
+pub fn parse(input: syn::parse::ParseStream) -> syn::Result<Vec<TypeDefinition>> {
+    fn parse_param(input: syn::parse::ParseStream) -> syn::Result<TypeDefinition> {
+        let target_name_ident: syn::Ident = input.parse()?;
+        let _: syn::Token![=>] = input.parse()?;
+        let ty: syn::Type = input.parse()?;
+
+        Ok(TypeDefinition {
+            target_name_ident,
+            ty,
+        })
+    }
+
+    let vec: Vec<_> = {
+        let content;
+        syn::braced!(content in input);
+        content
+            .parse_terminated(parse_param, syn::Token![,])?
+            .into_iter()
+            .collect()
+    };
+
+    {
+        let mut previous_names = HashSet::new();
+        for TypeDefinition {
+            target_name_ident, ..
+        } in &vec
+        {
+            let target_name = target_name_ident.to_string();
+            if previous_names.contains(&target_name) {
+                return Err(syn::Error::new(
+                    target_name_ident.span(),
+                    "duplicate target name",
+                ));
+            }
+            previous_names.insert(target_name);
+        }
+    }
+
+    Ok(vec)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use quote::ToTokens;
+    use syn::parse::Parser;
+
+    use super::{parse, TypeDefinition};
+
+    #[derive(Debug, PartialEq, Eq, typed_builder::TypedBuilder)]
+    pub struct TypeDefinitionForTest {
+        target_name: String,
+        ty: String,
+    }
+    impl From<TypeDefinition> for TypeDefinitionForTest {
+        fn from(value: TypeDefinition) -> Self {
+            let TypeDefinition {
+                target_name_ident,
+                ty,
+            } = value;
+
+            Self {
+                target_name: target_name_ident.to_string(),
+                ty: ty.into_token_stream().to_string(),
+            }
+        }
+    }
+
+    #[test]
+    fn basic() {
+        let ty_foo = quote::quote!(crate::Foo);
+        let ty_bar = quote::quote!(Box<dyn crate::Bar>);
+
+        let input = quote::quote!({ Foo => #ty_foo, Bar => #ty_bar });
+
+        let expected = vec![
+            TypeDefinitionForTest::builder()
+                .target_name("Foo".to_string())
+                .ty(ty_foo.to_string())
+                .build(),
+            TypeDefinitionForTest::builder()
+                .target_name("Bar".to_string())
+                .ty(ty_bar.to_string())
+                .build(),
+        ];
+
+        let actual = (|input: syn::parse::ParseStream| parse(input))
+            .parse2(input)
+            .expect("should be able to parse the input");
+        let actual: Vec<TypeDefinitionForTest> = actual.into_iter().map(|x| x.into()).collect();
+
+        assert_eq!(actual, expected)
+    }
+}
@@ -1,4 +1,4 @@
-use proc_macro2::TokenTree;
+use proc_macro2::{TokenStream, TokenTree};
 
 use crate::attributes::{__monomorphize_mod, define};
 
@@ -15,6 +15,12 @@ impl AttributeOptionsPair {
     pub fn __monomorphize_mod(&self) -> &__monomorphize_mod::AttributeOptions {
         &self.1
     }
+
+    /// Adds the declarations of a template extending the one being
+    /// monomorphized. See [`define::AttributeOptions::merge`].
+    pub fn extend(&mut self, derived: define::AttributeOptions) -> syn::Result<()> {
+        self.0.merge(derived)
+    }
 }
 
 impl syn::parse::Parse for AttributeOptionsPair {
@@ -55,14 +61,35 @@ impl AttributeOptionsPair {
 
         let declared_constructions = map_to_ident!(self.define().constructions());
         let declared_attr_substs = map_to_ident!(self.define().attribute_substitutions());
+        let declared_types = map_to_ident!(self.define().types());
+        let declared_consts = map_to_ident!(self.define().consts());
+        let declared_idents = map_to_ident!(self.define().idents());
         let defined_constructions = map_to_ident!(self.__monomorphize_mod().constructions());
         let defined_attr_substs =
             map_to_ident!(self.__monomorphize_mod().attribute_substitutions());
+        let defined_types = map_to_ident!(self.__monomorphize_mod().types());
+        let defined_consts = map_to_ident!(self.__monomorphize_mod().consts());
+        let defined_idents = map_to_ident!(self.__monomorphize_mod().idents());
 
-        let (undecl_constructions, undef_constructions) =
+        let (undecl_constructions, mut undef_constructions) =
             utils::diff_by_display(&declared_constructions, &defined_constructions);
-        let (undecl_attr_substs, undef_attr_substs) =
+        undef_constructions.retain(|target_name_ident| {
+            self.define().constructions().iter().any(|decl| {
+                decl.target_name_ident() == *target_name_ident && decl.default().is_none()
+            })
+        });
+        let (undecl_attr_substs, mut undef_attr_substs) =
             utils::diff_by_display(&declared_attr_substs, &defined_attr_substs);
+        undef_attr_substs.retain(|target_name_ident| {
+            self.define().attribute_substitutions().iter().any(|decl| {
+                decl.target_name_ident() == *target_name_ident && decl.default().is_none()
+            })
+        });
+        let (undecl_types, undef_types) = utils::diff_by_display(&declared_types, &defined_types);
+        let (undecl_consts, undef_consts) =
+            utils::diff_by_display(&declared_consts, &defined_consts);
+        let (undecl_idents, undef_idents) =
+            utils::diff_by_display(&declared_idents, &defined_idents);
 
         let mut err: Option<syn::Error> = None;
         #[derive(PartialEq, Eq)]
@@ -73,8 +100,14 @@ impl AttributeOptionsPair {
         for (what, which, unknown_target_names_ident) in [
             (What::Undecl, "constructions", undecl_constructions),
             (What::Undecl, "attribute_substitutions", undecl_attr_substs),
+            (What::Undecl, "types", undecl_types),
+            (What::Undecl, "consts", undecl_consts),
+            (What::Undecl, "idents", undecl_idents),
             (What::Undef, "constructions", undef_constructions),
             (What::Undef, "attribute_substitutions", undef_attr_substs),
+            (What::Undef, "types", undef_types),
+            (What::Undef, "consts", undef_consts),
+            (What::Undef, "idents", undef_idents),
         ] {
             for target_name_ident in unknown_target_names_ident {
                 let message = if what == What::Undecl {
@@ -101,6 +134,40 @@ impl AttributeOptionsPair {
             }
         }
 
+        for def in self.__monomorphize_mod().attribute_substitutions() {
+            let Some(def_ext) = def.parameter_list_extension() else {
+                continue;
+            };
+            let Some(decl_ext) = self
+                .define()
+                .attribute_substitutions()
+                .iter()
+                .find(|decl| decl.target_name_ident() == def.target_name_ident())
+                .and_then(|decl| decl.parameter_list_extension().as_ref())
+            else {
+                continue;
+            };
+            if utils::starts_with_parameters(def_ext.parameter_list(), decl_ext.parameter_list()) {
+                continue;
+            }
+            let new_err = syn::Error::new(
+                def.target_name_ident().span(),
+                format!(
+                    "the parameter list extension of `{}` should start with the declared parameters `{}`",
+                    def.target_name_ident(),
+                    TokenStream::from_iter(decl_ext.parameter_list().iter().cloned()),
+                ),
+            );
+            match err {
+                Some(ref mut err) => {
+                    err.combine(new_err);
+                }
+                None => {
+                    err = Some(new_err);
+                }
+            };
+        }
+
         match err {
             Some(err) => Err(err),
             None => Ok(()),
@@ -115,6 +182,29 @@ mod utils {
         hash::Hash,
     };
 
+    use proc_macro2::{TokenStream, TokenTree};
+    use quote::ToTokens;
+    use syn::{parse::Parser, punctuated::Punctuated};
+
+    /// Whether the parameters in `list` start with those in `prefix`.
+    pub fn starts_with_parameters(list: &[TokenTree], prefix: &[TokenTree]) -> bool {
+        let parse = |tts: &[TokenTree]| {
+            Punctuated::<syn::FnArg, syn::Token![,]>::parse_terminated
+                .parse2(TokenStream::from_iter(tts.iter().cloned()))
+                .map(|params| {
+                    params
+                        .into_iter()
+                        .map(|param| param.into_token_stream().to_string())
+                        .collect::<Vec<_>>()
+                })
+        };
+        let (Ok(list), Ok(prefix)) = (parse(list), parse(prefix)) else {
+            return false;
+        };
+
+        list.starts_with(&prefix)
+    }
+
     pub fn diff_by_display<'a, Item>(
         lefts: &'a [Item],
         rights: &'a [Item],
@@ -151,7 +241,7 @@ mod utils {
     mod tests {
         use std::collections::HashSet;
 
-        use super::diff_by_display;
+        use super::{diff_by_display, starts_with_parameters};
 
         #[test]
         fn it_works() {
@@ -169,6 +259,20 @@ mod utils {
                 HashSet::from([2])
             );
         }
+
+        #[test]
+        fn parameters() {
+            let list: Vec<_> = quote::quote!(pool: Pool<Sqlite>, n: i32)
+                .into_iter()
+                .collect();
+            let prefix: Vec<_> = quote::quote!(pool: Pool<Sqlite>).into_iter().collect();
+            let other: Vec<_> = quote::quote!(n: i32).into_iter().collect();
+
+            assert!(starts_with_parameters(&list, &prefix));
+            assert!(starts_with_parameters(&list, &list));
+            assert!(!starts_with_parameters(&list, &other));
+            assert!(!starts_with_parameters(&prefix, &list));
+        }
     }
 }
 
@@ -0,0 +1,52 @@
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+
+use crate::attributes::define;
+
+/// A template extending the one being monomorphized, passed to the attribute
+/// `__monomorphize_mod` after the usual options by the macro defined by
+/// `mod_template::define`.
+pub struct DerivedTemplate {
+    pub define_attr_opts: define::AttributeOptions,
+    pub mod_items: TokenStream,
+}
+
+/// Splits the options of the attribute `__monomorphize_mod` into the usual
+/// ones (the part in parentheses, a comma, and the part in braces) and the
+/// rest, i.e. the templates extending the one being monomorphized.
+pub fn split(attr: TokenStream) -> (TokenStream, TokenStream) {
+    let mut iter = attr.into_iter();
+    let usual: TokenStream = iter.by_ref().take(3).collect();
+    (usual, iter.collect())
+}
+
+/// Parses something like `((…options of define…) mod __ { … }) …`.
+pub fn parse_derived_templates(input: TokenStream) -> syn::Result<Vec<DerivedTemplate>> {
+    let mut derived_templates = vec![];
+    for tt in input {
+        let TokenTree::Group(group) = &tt else {
+            return Err(syn::Error::new(tt.span(), "unexpected"));
+        };
+        if group.delimiter() != Delimiter::Parenthesis {
+            return Err(syn::Error::new(tt.span(), "unexpected"));
+        }
+        let mut tts = group.stream().into_iter();
+        let define_attr_opts = match tts.next() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+                syn::parse2(group.stream())?
+            }
+            _ => return Err(syn::Error::new(group.span(), "unexpected")),
+        };
+        let mod_items = match tts.last() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                group.stream()
+            }
+            _ => return Err(syn::Error::new(group.span(), "unexpected")),
+        };
+        derived_templates.push(DerivedTemplate {
+            define_attr_opts,
+            mod_items,
+        });
+    }
+
+    Ok(derived_templates)
+}
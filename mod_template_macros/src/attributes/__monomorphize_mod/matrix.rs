@@ -0,0 +1,406 @@
+use proc_macro2::{Delimiter, Group, Ident, Punct, Spacing, TokenStream, TokenTree};
+use quote::TokenStreamExt;
+
+/// Expands the `matrix` block in the options of the attribute
+/// `__monomorphize_mod` (the part in braces), if any, into the options of every
+/// combination of the alternatives, with the name of the module suffixed by
+/// the labels of the chosen alternatives.
+///
+/// The blocks of the chosen alternatives are merged with the blocks outside
+/// the `matrix` block in token level, so the duplications between them are
+/// reported as usual when the merged options are parsed.
+pub fn expand(attr: TokenStream) -> syn::Result<Vec<TokenStream>> {
+    let tts: Vec<TokenTree> = attr.clone().into_iter().collect();
+    let [define_group @ TokenTree::Group(..), comma @ TokenTree::Punct(..), TokenTree::Group(instance_group)] =
+        &tts[..]
+    else {
+        // NOTE: let the parser of `AttributeOptionsPair` report errors.
+        return Ok(vec![attr]);
+    };
+
+    let (header, blocks) = split_header(instance_group.stream());
+    let Some(blocks) = blocks else {
+        return Ok(vec![attr]);
+    };
+    let mut blocks = parse_blocks(blocks)?;
+    let Some(matrix_index) = blocks.iter().position(|block| block.name_ident == "matrix") else {
+        return Ok(vec![attr]);
+    };
+    let matrix = blocks.remove(matrix_index);
+    if blocks.iter().any(|block| block.name_ident == "matrix") {
+        return Err(syn::Error::new(
+            matrix.name_ident.span(),
+            "duplicate matrix block",
+        ));
+    }
+    let axes = parse_axes(matrix.content)?;
+
+    let mut combinations: Vec<(Vec<&Ident>, Vec<&Block>)> = vec![(vec![], vec![])];
+    for axis in &axes {
+        combinations = combinations
+            .into_iter()
+            .flat_map(|(labels, blocks)| {
+                axis.alternatives.iter().map(move |alternative| {
+                    let mut labels = labels.clone();
+                    labels.push(&alternative.label_ident);
+                    let mut blocks = blocks.clone();
+                    blocks.extend(&alternative.blocks);
+                    (labels, blocks)
+                })
+            })
+            .collect();
+    }
+
+    let mut output = vec![];
+    for (labels, alternative_blocks) in combinations {
+        let header = rename_mod(header.clone(), &labels)?;
+        let merged = merge_blocks(blocks.iter().chain(alternative_blocks));
+
+        let mut new_instance_group = Group::new(Delimiter::Brace, quote::quote!(#header #merged));
+        new_instance_group.set_span(instance_group.span());
+
+        let mut attr = TokenStream::new();
+        attr.append(define_group.clone());
+        attr.append(comma.clone());
+        attr.append(new_instance_group);
+        output.push(attr);
+    }
+
+    Ok(output)
+}
+
+#[derive(Clone)]
+pub(super) struct Block {
+    pub(super) name_ident: Ident,
+    pub(super) content: TokenStream,
+}
+
+struct Axis {
+    alternatives: Vec<Alternative>,
+}
+
+struct Alternative {
+    label_ident: Ident,
+    blocks: Vec<Block>,
+}
+
+/// Splits the options into the module header (including the `;`) and the rest,
+/// which is absent if there is no `;`.
+fn split_header(input: TokenStream) -> (TokenStream, Option<TokenStream>) {
+    let mut header = TokenStream::new();
+    let mut iter = input.into_iter();
+    for tt in iter.by_ref() {
+        let is_semicolon = matches!(&tt, TokenTree::Punct(punct) if punct.as_char() == ';');
+        header.append(tt);
+        if is_semicolon {
+            return (header, Some(iter.collect()));
+        }
+    }
+    (header, None)
+}
+
+/// Parses something like `foo { … }, bar { … },`.
+pub(super) fn parse_blocks(input: TokenStream) -> syn::Result<Vec<Block>> {
+    let mut blocks = vec![];
+    let mut iter = input.into_iter();
+    while let Some(tt) = iter.next() {
+        let TokenTree::Ident(name_ident) = tt else {
+            return Err(syn::Error::new(tt.span(), "expected the name of a block"));
+        };
+        let content = match iter.next() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                group.stream()
+            }
+            Some(tt) => return Err(syn::Error::new(tt.span(), "expected a block in braces")),
+            None => {
+                return Err(syn::Error::new(
+                    name_ident.span(),
+                    "expected a block in braces after this",
+                ))
+            }
+        };
+        blocks.push(Block {
+            name_ident,
+            content,
+        });
+        match iter.next() {
+            None => {}
+            Some(TokenTree::Punct(punct)) if punct.as_char() == ',' => {}
+            Some(tt) => return Err(syn::Error::new(tt.span(), "expected `,`")),
+        }
+    }
+    Ok(blocks)
+}
+
+/// Parses something like `axis { label => { blocks… }, … }, …`.
+fn parse_axes(input: TokenStream) -> syn::Result<Vec<Axis>> {
+    let mut axes = vec![];
+    for axis in parse_blocks(input)? {
+        let mut alternatives: Vec<Alternative> = vec![];
+        let mut iter = axis.content.into_iter();
+        while let Some(tt) = iter.next() {
+            let TokenTree::Ident(label_ident) = tt else {
+                return Err(syn::Error::new(
+                    tt.span(),
+                    "expected the label of an alternative",
+                ));
+            };
+            if alternatives.iter().any(|x| x.label_ident == label_ident) {
+                return Err(syn::Error::new(label_ident.span(), "duplicate label"));
+            }
+            match (iter.next(), iter.next()) {
+                (Some(TokenTree::Punct(eq)), Some(TokenTree::Punct(gt)))
+                    if eq.as_char() == '=' && gt.as_char() == '>' => {}
+                _ => {
+                    return Err(syn::Error::new(
+                        label_ident.span(),
+                        "expected `=>` after this",
+                    ))
+                }
+            }
+            let blocks = match iter.next() {
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                    parse_blocks(group.stream())?
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        label_ident.span(),
+                        "expected blocks in braces for this alternative",
+                    ))
+                }
+            };
+            alternatives.push(Alternative {
+                label_ident,
+                blocks,
+            });
+            match iter.next() {
+                None => {}
+                Some(TokenTree::Punct(punct)) if punct.as_char() == ',' => {}
+                Some(tt) => return Err(syn::Error::new(tt.span(), "expected `,`")),
+            }
+        }
+        if alternatives.is_empty() {
+            return Err(syn::Error::new(
+                axis.name_ident.span(),
+                "an axis of the matrix should have at least one alternative",
+            ));
+        }
+        axes.push(Axis { alternatives });
+    }
+    Ok(axes)
+}
+
+/// Suffixes the name of the module or the function (the identifier after the
+/// `mod` or `fn` keyword) with the given labels.
+fn rename_mod(header: TokenStream, labels: &[&Ident]) -> syn::Result<TokenStream> {
+    let mut tts: Vec<TokenTree> = header.into_iter().collect();
+    // NOTE: the attributes of the header are groups, so only the keywords at
+    // the top level are looked at.
+    let keyword_index = tts.iter().position(|tt| {
+        matches!(tt, TokenTree::Ident(ident) if ident == "mod" || ident == "fn" || ident == "impl")
+    });
+    let name_ident = match keyword_index.map(|index| (&tts[index], index)) {
+        Some((TokenTree::Ident(keyword), _)) if keyword == "impl" => {
+            return Err(syn::Error::new(
+                keyword.span(),
+                "a `matrix` block can't be used with an instance of a template of an `impl` block, since it has no name to be suffixed",
+            ));
+        }
+        Some((_, index)) => match tts.get_mut(index + 1) {
+            Some(TokenTree::Ident(ident)) => Some(ident),
+            _ => None,
+        },
+        None => None,
+    };
+    let Some(name_ident) = name_ident else {
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "expected the name of the module",
+        ));
+    };
+    // NOTE: inline instances (`mod _;`) have no name to be suffixed. Their
+    // items collide, which is reported as usual.
+    if name_ident == "_" {
+        return Ok(tts.into_iter().collect());
+    }
+    let mut name = name_ident.to_string();
+    for label in labels {
+        name.push('_');
+        name.push_str(&label.to_string());
+    }
+    *name_ident = Ident::new(&name, name_ident.span());
+
+    Ok(tts.into_iter().collect())
+}
+
+/// Merges the contents of the blocks with the same name, keeping the order in
+/// which the names first appear.
+pub(super) fn merge_blocks<'a>(blocks: impl Iterator<Item = &'a Block>) -> TokenStream {
+    let mut merged: Vec<(Ident, TokenStream)> = vec![];
+    for block in blocks {
+        let content = match merged
+            .iter_mut()
+            .find(|(name_ident, _)| *name_ident == block.name_ident)
+        {
+            Some((_, content)) => content,
+            None => {
+                merged.push((block.name_ident.clone(), TokenStream::new()));
+                &mut merged.last_mut().expect("it was just pushed").1
+            }
+        };
+        // NOTE: items in the `items` and `overrides` blocks are not separated
+        // by commas.
+        let is_comma_separated = block.name_ident != "items" && block.name_ident != "overrides";
+        let needs_comma = is_comma_separated
+            && !content.is_empty()
+            && !matches!(
+                content.clone().into_iter().last(),
+                Some(TokenTree::Punct(punct)) if punct.as_char() == ','
+            );
+        if needs_comma {
+            content.append(Punct::new(',', Spacing::Alone));
+        }
+        content.extend(block.content.clone());
+    }
+
+    let mut output = TokenStream::new();
+    for (name_ident, content) in merged {
+        output.append(name_ident);
+        output.append(Group::new(Delimiter::Brace, content));
+        output.append(Punct::new(',', Spacing::Alone));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand;
+
+    #[test]
+    fn basic() {
+        let input = quote::quote!(
+            (macro_rules! macro_name; constructions(FOO -> i32, BAR -> i32)),
+            {
+                pub mod a_mod;
+                constructions { FOO => 1 },
+                matrix {
+                    x {
+                        one => { constructions { BAR => 1 } },
+                        two => { constructions { BAR => 2 }, skip { baz } },
+                    },
+                    y {
+                        a => { items { fn a() {} } },
+                        b => { items { fn b() {} } },
+                    },
+                },
+                items { fn c() {} },
+            }
+        );
+
+        let expected = [
+            ("a_mod_one_a", "constructions { FOO => 1 , BAR => 1 } , items { fn c() {} fn a() {} } ,"),
+            ("a_mod_one_b", "constructions { FOO => 1 , BAR => 1 } , items { fn c() {} fn b() {} } ,"),
+            ("a_mod_two_a", "constructions { FOO => 1 , BAR => 2 } , items { fn c() {} fn a() {} } , skip { baz } ,"),
+            ("a_mod_two_b", "constructions { FOO => 1 , BAR => 2 } , items { fn c() {} fn b() {} } , skip { baz } ,"),
+        ]
+        .map(|(name, blocks)| {
+            let name = quote::format_ident!("{}", name);
+            let blocks: proc_macro2::TokenStream = blocks.parse().unwrap();
+            quote::quote!(
+                (macro_rules! macro_name; constructions(FOO -> i32, BAR -> i32)),
+                { pub mod #name; #blocks }
+            )
+            .to_string()
+        });
+
+        let actual = expand(input).unwrap();
+        let actual: Vec<_> = actual.into_iter().map(|x| x.to_string()).collect();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn fn_headers() {
+        let input = quote::quote!(
+            (macro_rules! macro_name; consts(N: usize)),
+            {
+                #[doc = "fn not_the_name"]
+                pub(crate) fn a_fn;
+                matrix {
+                    n {
+                        one => { consts { N => 1 } },
+                    },
+                },
+            }
+        );
+
+        let expected = quote::quote!(
+            (macro_rules! macro_name; consts(N: usize)),
+            {
+                #[doc = "fn not_the_name"]
+                pub(crate) fn a_fn_one;
+                consts { N => 1 },
+            }
+        );
+
+        let actual = expand(input).unwrap();
+        let actual: Vec<_> = actual.into_iter().map(|x| x.to_string()).collect();
+
+        assert_eq!(actual, vec![expected.to_string()]);
+    }
+
+    #[test]
+    fn impl_headers() {
+        let input = quote::quote!(
+            (macro_rules! macro_name; consts(N: usize)),
+            {
+                impl<U> crate::Foo<U> where U: crate::Bar;
+                matrix {
+                    n {
+                        one => { consts { N => 1 } },
+                    },
+                },
+            }
+        );
+
+        let err = expand(input).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "a `matrix` block can't be used with an instance of a template of an `impl` block, since it has no name to be suffixed"
+        );
+    }
+
+    #[test]
+    fn duplicate_labels() {
+        let input = quote::quote!(
+            (macro_rules! macro_name; constructions(FOO -> i32)),
+            {
+                mod a_mod;
+                matrix {
+                    x {
+                        one => { constructions { FOO => 1 } },
+                        one => { constructions { FOO => 2 } },
+                    },
+                },
+            }
+        );
+
+        let err = expand(input).unwrap_err();
+
+        assert_eq!(err.to_string(), "duplicate label");
+    }
+
+    #[test]
+    fn without_matrix() {
+        let input = quote::quote!(
+            (macro_rules! macro_name;),
+            { mod a_mod; constructions { FOO => 1 } }
+        );
+
+        let actual = expand(input.clone()).unwrap();
+        let actual: Vec<_> = actual.into_iter().map(|x| x.to_string()).collect();
+
+        assert_eq!(actual, vec![input.to_string()]);
+    }
+}
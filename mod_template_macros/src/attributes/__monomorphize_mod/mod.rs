@@ -1,33 +1,109 @@
 pub(crate) mod attribute_options;
 mod attribute_options_pair;
+pub(crate) mod extends;
+mod matrix;
+mod table;
 
-use std::{collections::HashMap, rc::Rc};
+use std::collections::{HashMap, HashSet};
 
-use proc_macro2::{TokenStream, TokenTree};
+use proc_macro2::{Group, Ident, Span, TokenStream, TokenTree};
 use quote::ToTokens;
+use syn::{
+    ext::IdentExt,
+    spanned::Spanned,
+    visit_mut::{self, VisitMut},
+};
 
 use crate::{
     attributes::__monomorphize_mod::attribute_options_pair::AttributeOptionsPair,
+    attributes::{define::TemplateKind, extend_field_list, extend_parameter_list},
     helper_attributes::{
         __CONSTRUCT::AttributeOptions as ConstructHelperAttributeOptions,
         __SUBSTITUTE::AttributeOptions as SubstituteHelperAttributeOptions,
     },
-    utils::substitute_attributes::{substitute_attributes, Substituter},
+    utils::{
+        inject_into_mods::{inject_into_mods, inject_into_nested_mods},
+        item_names::{item_name_ident, item_names},
+        nested_instances::{forward_into_nested_instances, ForwardedKind},
+        substitute_attributes::{substitute_attributes, Substituter},
+    },
 };
 
+use self::attribute_options::mod_header::ModToken;
 pub use self::attribute_options::AttributeOptions;
 
 pub fn __monomorphize_mod(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let opts_pair: AttributeOptionsPair = match syn::parse2(attr) {
+    let (attr, derived) = extends::split(attr);
+    let attrs = table::expand(attr).and_then(|attrs| {
+        attrs
+            .into_iter()
+            .map(matrix::expand)
+            .collect::<syn::Result<Vec<_>>>()
+    });
+    let attrs: Vec<TokenStream> = match attrs {
+        Ok(attrs) => attrs.into_iter().flatten().collect(),
+        Err(err) => return err.to_compile_error(),
+    };
+
+    attrs
+        .into_iter()
+        .map(|attr| monomorphize_mod(attr, derived.clone(), item.clone()))
+        .collect()
+}
+
+fn monomorphize_mod(attr: TokenStream, derived: TokenStream, item: TokenStream) -> TokenStream {
+    let mut opts_pair: AttributeOptionsPair = match syn::parse2(attr) {
         Ok(pair) => pair,
         Err(err) => return err.to_compile_error(),
     };
+    let derived_templates = match extends::parse_derived_templates(derived) {
+        Ok(derived_templates) => derived_templates,
+        Err(err) => return err.to_compile_error(),
+    };
+    let kind = TemplateKind::of(&item);
+    if let (true, Some(derived_template)) = (kind != TemplateKind::Mod, derived_templates.first()) {
+        return syn::Error::new(
+            derived_template
+                .define_attr_opts
+                .mbe_header()
+                .name_ident()
+                .span(),
+            format!(
+                "a template of {} can't be extended",
+                kind_with_article(kind)
+            ),
+        )
+        .to_compile_error();
+    }
+    let mut derived_mod_items = TokenStream::new();
+    for derived_template in derived_templates {
+        // NOTE: the error is reported by the compiler check dummy of the
+        // derived template already, which is made whether or not the template
+        // is instantiated.
+        if opts_pair.extend(derived_template.define_attr_opts).is_err() {
+            return TokenStream::new();
+        }
+        derived_mod_items.extend(derived_template.mod_items);
+    }
     if let Err(err) = opts_pair.validate() {
         let mut output = TokenStream::new();
         output.extend(err.to_compile_error());
 
         return output;
     }
+    if let Err(err) = check_instance_kind(&opts_pair, kind) {
+        return err.to_compile_error();
+    }
+    let result = match kind {
+        TemplateKind::Mod => Ok(None),
+        TemplateKind::Fn => monomorphize_fn(item.clone(), &opts_pair).map(Some),
+        TemplateKind::Impl => monomorphize_impl(item.clone(), &opts_pair).map(Some),
+    };
+    match result {
+        Ok(None) => {}
+        Ok(Some(output)) => return output,
+        Err(err) => return err.to_compile_error(),
+    }
 
     let mod_items = {
         let mod_group = (item.into_iter().last())
@@ -35,11 +111,26 @@ pub fn __monomorphize_mod(attr: TokenStream, item: TokenStream) -> TokenStream {
         let TokenTree::Group(mod_group) = mod_group else {
             panic!("the attribute `mod_template::define` should guarantee that the last element of the input item is a group");
         };
-        mod_group.stream()
+        let mut mod_items = mod_group.stream();
+        mod_items.extend(derived_mod_items);
+        mod_items
     };
     let opts = opts_pair.__monomorphize_mod();
-    let type_map = opts_pair.define().build_type_map();
-    let output_items = match monomorphize_items(mod_items, opts, type_map) {
+    let mod_items = match skip_items(mod_items, opts.skip()) {
+        Ok(mod_items) => mod_items,
+        Err(err) => return err.to_compile_error(),
+    };
+    let mod_items = match override_items(mod_items, opts.overrides()) {
+        Ok(mod_items) => mod_items,
+        Err(err) => return err.to_compile_error(),
+    };
+    let is_inline = matches!(opts.mod_header().mod_token, ModToken::Inline(..));
+    if is_inline {
+        if let Err(err) = check_inlined_parameter_usages(mod_items.clone(), &opts_pair) {
+            return err.to_compile_error();
+        }
+    }
+    let output_items = match monomorphize_items(mod_items, &opts_pair) {
         Ok(output_items) => output_items,
         Err(errs) => {
             // TODO: DRY
@@ -50,75 +141,360 @@ pub fn __monomorphize_mod(attr: TokenStream, item: TokenStream) -> TokenStream {
             return output;
         }
     };
-    let mod_header = opts.mod_header().to_token_stream();
+    let parameter_items = make_parameter_items(&opts_pair);
+    let output_items = if is_inline {
+        match inject_into_inlined_items(output_items, &parameter_items) {
+            Ok(output_items) => output_items,
+            Err(err) => return err.to_compile_error(),
+        }
+    } else {
+        inject_into_mods(output_items, &parameter_items)
+    };
+    let bound_checks = make_type_bound_checks(&opts_pair);
+    let items = opts.items();
+
+    match &opts.mod_header().mod_token {
+        ModToken::Inline(_, underscore_token) => {
+            let inlined_items = quote::quote! { #output_items #(#items)* };
+            let markers =
+                match make_inlined_item_markers(inlined_items.clone(), underscore_token.span) {
+                    Ok(markers) => markers,
+                    Err(err) => return err.to_compile_error(),
+                };
+            quote::quote! { #inlined_items #bound_checks #markers }
+        }
+        _ => {
+            let mod_header = opts.mod_header();
+            quote::quote! { #mod_header { #output_items #(#items)* #bound_checks } }
+        }
+    }
+}
+
+/// Checks that the types and consts declared in the `types` and `consts`
+/// blocks are only used in the bodies of functions and in the nested modules
+/// of an inline instance (`mod _;`), where the parameter items are put. See
+/// [`inject_into_inlined_items`].
+fn check_inlined_parameter_usages(
+    mod_items: TokenStream,
+    opts_pair: &AttributeOptionsPair,
+) -> syn::Result<()> {
+    fn find_mention(tokens: TokenStream, target_name_ident: &Ident) -> Option<Ident> {
+        tokens.into_iter().find_map(|tt| match tt {
+            TokenTree::Ident(ident) if ident == *target_name_ident => Some(ident),
+            TokenTree::Group(group) => find_mention(group.stream(), target_name_ident),
+            _ => None,
+        })
+    }
+
+    let define_opts = opts_pair.define();
+    if define_opts.types().is_empty() && define_opts.consts().is_empty() {
+        return Ok(());
+    }
+
+    let file: syn::File = syn::parse2(mod_items)?;
+    let mut outside_bodies = TokenStream::new();
+    for item in file.items {
+        match item {
+            syn::Item::Fn(item_fn) => item_fn.sig.to_tokens(&mut outside_bodies),
+            syn::Item::Impl(item_impl) => {
+                item_impl.generics.to_tokens(&mut outside_bodies);
+                if let Some((_, trait_path, _)) = &item_impl.trait_ {
+                    trait_path.to_tokens(&mut outside_bodies);
+                }
+                item_impl.self_ty.to_tokens(&mut outside_bodies);
+                for impl_item in &item_impl.items {
+                    match impl_item {
+                        syn::ImplItem::Fn(impl_item_fn) => {
+                            impl_item_fn.sig.to_tokens(&mut outside_bodies)
+                        }
+                        impl_item => impl_item.to_tokens(&mut outside_bodies),
+                    }
+                }
+            }
+            // NOTE: the bodies of `macro_rules!` are used where they are
+            // expanded.
+            syn::Item::Mod(..)
+            | syn::Item::Macro(syn::ItemMacro {
+                ident: Some(..), ..
+            }) => {}
+            item => item.to_tokens(&mut outside_bodies),
+        }
+    }
+
+    let target_name_idents = (define_opts.types().iter())
+        .map(|decl| decl.target_name_ident())
+        .chain(
+            define_opts
+                .consts()
+                .iter()
+                .map(|decl| decl.target_name_ident()),
+        );
+    for target_name_ident in target_name_idents {
+        if let Some(ident) = find_mention(outside_bodies.clone(), target_name_ident) {
+            return Err(syn::Error::new(
+                ident.span(),
+                format!(
+                    "`{}` can only be used in the bodies of functions and in nested modules of an inline instance (`mod _;`)",
+                    ident
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Puts the parameter items at the beginning of the bodies of the top-level
+/// functions and methods of an inline instance (`mod _;`), and into its nested
+/// modules. Unlike in a module, putting them at the top level would put them
+/// directly into the scope of the macro call, where they would collide with
+/// the ones of other inline instances, or the items of the caller.
+fn inject_into_inlined_items(
+    items: TokenStream,
+    parameter_items: &TokenStream,
+) -> syn::Result<TokenStream> {
+    if parameter_items.is_empty() {
+        return Ok(items);
+    }
+
+    let mut file: syn::File = syn::parse2(inject_into_nested_mods(items, parameter_items))?;
+    let inject_into_block = |block: &mut syn::Block| {
+        let stmts = &block.stmts;
+        *block = syn::parse_quote!({ #parameter_items #(#stmts)* });
+    };
+    for item in &mut file.items {
+        match item {
+            syn::Item::Fn(item_fn) => inject_into_block(&mut item_fn.block),
+            syn::Item::Impl(item_impl) => {
+                for impl_item in &mut item_impl.items {
+                    if let syn::ImplItem::Fn(impl_item_fn) = impl_item {
+                        inject_into_block(&mut impl_item_fn.block);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(file.into_token_stream())
+}
+
+/// Hidden structs named after the items put directly into the scope of the
+/// macro call by an inline instance (`mod _;`), spanned at its header.
+///
+/// NOTE: when items collide since multiple instances are inlined into the same
+/// scope, the compiler reports the collisions at the template. The markers
+/// collide as well, so that the instances causing them are reported too.
+fn make_inlined_item_markers(inlined_items: TokenStream, span: Span) -> syn::Result<TokenStream> {
+    let file: syn::File = syn::parse2(inlined_items)?;
+    let names = item_names(&file.items);
+
+    let mut marker_names: Vec<String> = names
+        .iter()
+        .map(|name| format!("__mod_template__inlined__{}", name.unraw()))
+        .collect();
+    // NOTE: the same name may be used in different namespaces.
+    marker_names.sort();
+    marker_names.dedup();
+
+    let mut output = TokenStream::new();
+    for marker_name in marker_names {
+        let marker_ident = Ident::new(&marker_name, span);
+        quote::quote_spanned!(span=>
+            #[doc(hidden)]
+            #[allow(dead_code, non_camel_case_types)]
+            struct #marker_ident;
+        )
+        .to_tokens(&mut output);
+    }
+    Ok(output)
+}
+
+pub(crate) fn kind_with_article(kind: TemplateKind) -> String {
+    match kind {
+        TemplateKind::Impl => format!("an {}", kind.description()),
+        _ => format!("a {}", kind.description()),
+    }
+}
+
+/// Checks that the instance is of the same kind as the template, and that the
+/// blocks that apply only to modules are absent for other kinds.
+fn check_instance_kind(opts_pair: &AttributeOptionsPair, kind: TemplateKind) -> syn::Result<()> {
+    let opts = opts_pair.__monomorphize_mod();
+    let mod_header = opts.mod_header();
+    if mod_header.kind() != kind {
+        let keyword_span = match &mod_header.mod_token {
+            ModToken::Mod(mod_token, _) | ModToken::Inline(mod_token, _) => mod_token.span,
+            ModToken::Fn(fn_token, _) => fn_token.span,
+            ModToken::Impl(impl_token, ..) => impl_token.span,
+        };
+        let example = match kind {
+            TemplateKind::Impl => "impl «type»;",
+            TemplateKind::Fn => "fn «name»;",
+            TemplateKind::Mod => "mod «name»;",
+        };
+        return Err(syn::Error::new(
+            keyword_span,
+            format!(
+                "the template is {}, so the instance should be declared like `{}`",
+                kind_with_article(kind),
+                example
+            ),
+        ));
+    }
+    if kind == TemplateKind::Mod {
+        return Ok(());
+    }
+
+    let block_spans = [
+        ("items", opts.items().first().map(|x| x.span())),
+        ("skip", opts.skip().first().map(|x| x.span())),
+        ("overrides", opts.overrides().first().map(|x| x.span())),
+    ];
+    for (which, span) in block_spans {
+        if let Some(span) = span {
+            return Err(syn::Error::new(
+                span,
+                format!(
+                    "the `{}` block can't be used with a template of {}",
+                    which,
+                    kind_with_article(kind)
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Monomorphizes a template of a function into a function named after the
+/// header of the instance. The attributes in the header come before the ones
+/// of the function in the template.
+fn monomorphize_fn(
+    input_item: TokenStream,
+    opts_pair: &AttributeOptionsPair,
+) -> syn::Result<TokenStream> {
+    let output_item = monomorphize_items(input_item, opts_pair)?;
+    let mut item_fn: syn::ItemFn = syn::parse2(output_item)?;
+
+    let mod_header = opts_pair.__monomorphize_mod().mod_header();
+    let ModToken::Fn(_, ident) = &mod_header.mod_token else {
+        unreachable!("the kind of the instance should already be checked")
+    };
+    item_fn.attrs.splice(0..0, mod_header.attrs.iter().cloned());
+    item_fn.vis = mod_header.vis.clone();
+    item_fn.sig.ident = ident.clone();
+    inject_into_body(&mut item_fn.block, opts_pair);
+
+    Ok(item_fn.into_token_stream())
+}
+
+/// Monomorphizes a template of an `impl` block into an `impl` block for the
+/// type in the header of the instance. The attributes and the generics in the
+/// header come before the ones in the template.
+fn monomorphize_impl(
+    input_item: TokenStream,
+    opts_pair: &AttributeOptionsPair,
+) -> syn::Result<TokenStream> {
+    let output_item = monomorphize_items(input_item, opts_pair)?;
+    let mut item_impl: syn::ItemImpl = syn::parse2(output_item)?;
+
+    let mod_header = opts_pair.__monomorphize_mod().mod_header();
+    let ModToken::Impl(_, generics, self_ty) = &mod_header.mod_token else {
+        unreachable!("the kind of the instance should already be checked")
+    };
+    item_impl
+        .attrs
+        .splice(0..0, mod_header.attrs.iter().cloned());
+    item_impl.self_ty = self_ty.clone();
+    // NOTE: lifetimes must come before other generic parameters.
+    let (lifetimes, others): (Vec<_>, Vec<_>) = (generics.params.iter().cloned())
+        .chain(std::mem::take(&mut item_impl.generics.params))
+        .partition(|param| matches!(param, syn::GenericParam::Lifetime(..)));
+    item_impl.generics.params = lifetimes.into_iter().chain(others).collect();
+    if let Some(where_clause) = &generics.where_clause {
+        (item_impl.generics.make_where_clause().predicates)
+            .extend(where_clause.predicates.iter().cloned());
+    }
+    for impl_item in &mut item_impl.items {
+        if let syn::ImplItem::Fn(impl_item_fn) = impl_item {
+            inject_into_body(&mut impl_item_fn.block, opts_pair);
+        }
+    }
 
-    let output = quote::quote! { #mod_header { #output_items } };
+    Ok(item_impl.into_token_stream())
+}
 
-    output.to_token_stream()
+/// Puts the parameter items and the type bound checks at the beginning of the
+/// body of a function in a template of a function or an `impl` block, since
+/// there is no module to put them in.
+fn inject_into_body(block: &mut syn::Block, opts_pair: &AttributeOptionsPair) {
+    let parameter_items = make_parameter_items(opts_pair);
+    let bound_checks = make_type_bound_checks(opts_pair);
+    let stmts = &block.stmts;
+    *block = syn::parse_quote!({ #parameter_items #bound_checks #(#stmts)* });
 }
 
 const EXPECT_AVAILABLE: &str = "the availability of the definition should already be checked by calling `opts_pair.validate()` in the outer function";
-const EXPECT_CONSTRUCTION_TYPE_AVAILABLE: &str =
-    "any available construction should have a corresponding type";
 
 fn monomorphize_items<'a>(
     input_item: TokenStream,
-    opts: &'a AttributeOptions,
-    type_map: HashMap<String, syn::Type>,
+    opts_pair: &'a AttributeOptionsPair,
 ) -> Result<TokenStream, syn::Error> {
-    let opts = Rc::new(opts);
-    let opts_for_construct = opts.clone();
-    let opts_for_substitute = opts.clone();
+    let opts = opts_pair.__monomorphize_mod();
+    let type_map = opts_pair.define().build_type_map();
+    let construction_map = build_construction_map(opts_pair);
+    let crate_path = opts_pair.define().crate_path();
 
     let mut attr_map: HashMap<String, Box<Substituter<'a>>> = HashMap::new();
     attr_map.insert(
         "__CONSTRUCT".to_string(),
         Box::new(move |meta| {
-            let opts = &opts_for_construct;
-
             let meta = meta.require_list()?;
             let helper_opts: ConstructHelperAttributeOptions = syn::parse2(meta.tokens.clone())?;
 
             let mut output = TokenStream::new();
             for construction in helper_opts.constructions() {
-                let target_name = construction.target_name_ident().to_string();
-                let def = opts
-                    .constructions()
-                    .iter()
-                    .find(|x| *x.target_name_ident() == target_name)
-                    .expect(EXPECT_AVAILABLE);
+                let target_name_ident = construction.target_name_ident();
+                let target_name = target_name_ident.to_string();
                 let pattern_to_construct = construction.pattern_to_construct();
-                let ty = type_map
-                    .get(&target_name)
-                    .expect(EXPECT_CONSTRUCTION_TYPE_AVAILABLE);
-                let construction = def.construction();
+                // NOTE: the compiler check dummies of templates extending
+                // other ones are made separately, so unknown names in the
+                // former may reach here as well.
+                let Some(ty) = type_map.get(&target_name) else {
+                    return Err(unknown_target_name_error(
+                        target_name_ident,
+                        "constructions",
+                    ));
+                };
+                let construction = construction_map.get(&target_name).expect(EXPECT_AVAILABLE);
+                let construction = resolved_at(construction.clone(), meta.path.span());
                 quote::quote!(
-                    #[::mod_template::construct(#pattern_to_construct: #ty = #construction)])
+                    #[#crate_path::construct(#pattern_to_construct: #ty = #construction)])
                 .to_tokens(&mut output);
             }
 
             Ok(output)
         }),
     );
+    let crate_path = opts_pair.define().crate_path();
     attr_map.insert(
         "__SUBSTITUTE".to_string(),
         Box::new(move |meta| {
-            let opts = &opts_for_substitute;
-
             let meta = meta.require_list()?;
             let helper_opts: SubstituteHelperAttributeOptions = syn::parse2(meta.tokens.clone())?;
 
             let mut output = TokenStream::new();
-            let def = opts
-                .attribute_substitutions()
-                .iter()
-                .find(|x| *x.target_name_ident() == *helper_opts.target_name_ident())
-                .expect(EXPECT_AVAILABLE);
-            if let Some(ext) = def.parameter_list_extension() {
-                quote::quote!(#[::mod_template::extend_parameter_list(#ext)])
-                    .to_tokens(&mut output);
+            let (new_attributes, ext, field_ext) =
+                find_attribute_substitution(opts_pair, helper_opts.target_name_ident())?;
+            if let Some(ext) = ext {
+                let ext = resolved_at(ext.to_token_stream(), meta.path.span());
+                quote::quote!(#[#crate_path::extend_parameter_list(#ext)]).to_tokens(&mut output);
             }
-            for new_attribute in def.new_attributes() {
+            if let Some(field_ext) = field_ext {
+                quote::quote!(#[#crate_path::extend_field_list(#field_ext)]).to_tokens(&mut output);
+            }
+            for new_attribute in new_attributes {
                 new_attribute.to_tokens(&mut output)
             }
 
@@ -126,7 +502,355 @@ fn monomorphize_items<'a>(
         }),
     );
 
-    substitute_attributes(input_item, &mut attr_map)
+    let ident_map: HashMap<String, Ident> = opts
+        .idents()
+        .iter()
+        .map(|def| (def.target_name_ident().to_string(), def.ident().clone()))
+        .collect();
+
+    let output = substitute_attributes(input_item, &mut attr_map, &ident_map)?;
+
+    let construction_map = build_construction_map(opts_pair);
+    let forwarder = |kind: ForwardedKind, target_name_ident: &Ident| match kind {
+        ForwardedKind::Construction => {
+            let Some(construction) = construction_map.get(&target_name_ident.to_string()) else {
+                return Err(unknown_target_name_error(
+                    target_name_ident,
+                    "constructions",
+                ));
+            };
+            Ok(construction.clone())
+        }
+        ForwardedKind::AttributeSubstitution => {
+            let (new_attributes, ext, field_ext) =
+                find_attribute_substitution(opts_pair, target_name_ident)?;
+            let ext = match ext {
+                Some(ext) => ext.to_token_stream(),
+                None => quote::quote!(..),
+            };
+            let field_list = field_ext.map(|field_ext| {
+                let field_list = TokenStream::from_iter(field_ext.field_list().iter().cloned());
+                quote::quote!({ #field_list })
+            });
+            Ok(quote::quote!(#(#new_attributes)* (#ext) #field_list))
+        }
+    };
+    forward_into_nested_instances(output, &forwarder, true)
+}
+
+/// Finds the new attributes and the parameter list extension that an attribute
+/// substitution should be substituted with, either from the macro call or from
+/// the declaration, along with the field list extension from the macro call.
+fn find_attribute_substitution<'a>(
+    opts_pair: &'a AttributeOptionsPair,
+    target_name_ident: &Ident,
+) -> syn::Result<(
+    &'a Vec<syn::Attribute>,
+    Option<&'a extend_parameter_list::AttributeOptions>,
+    Option<&'a extend_field_list::AttributeOptions>,
+)> {
+    let def = opts_pair
+        .__monomorphize_mod()
+        .attribute_substitutions()
+        .iter()
+        .find(|x| x.target_name_ident() == target_name_ident);
+    // NOTE: nested instances are not checked by compiler check dummies, and
+    // the ones of templates extending other ones are made separately, so
+    // unknown names may reach here.
+    let Some(decl) = opts_pair
+        .define()
+        .attribute_substitutions()
+        .iter()
+        .find(|x| x.target_name_ident() == target_name_ident)
+    else {
+        return Err(unknown_target_name_error(
+            target_name_ident,
+            "attribute_substitutions",
+        ));
+    };
+    // NOTE: the extension in the macro call should already be checked to start
+    // with the declared one, if any.
+    let ext = match def {
+        Some(def) if def.parameter_list_extension().is_some() => def.parameter_list_extension(),
+        _ => decl.parameter_list_extension(),
+    };
+    let new_attributes = match def {
+        Some(def) => def.new_attributes(),
+        None => decl.default().expect(EXPECT_AVAILABLE),
+    };
+    let field_ext = def.and_then(|def| def.field_list_extension().as_ref());
+
+    Ok((new_attributes, ext.as_ref(), field_ext))
+}
+
+/// Maps the target names of constructions to the expressions specified in the
+/// macro call, or to the default ones declared in the attribute
+/// `mod_template::define` if absent.
+///
+/// NOTE: the expressions should be resolved at where they are used in the
+/// template, so that they can refer to the parameters declared in the template
+/// (e.g. by the `attribute_substitutions` block), and vice versa, despite
+/// hygiene. A template extending another one has a hygiene context of its own.
+fn build_construction_map(opts_pair: &AttributeOptionsPair) -> HashMap<String, TokenStream> {
+    let mut construction_map: HashMap<String, TokenStream> = HashMap::new();
+    for decl in opts_pair.define().constructions() {
+        if let Some(default) = decl.default() {
+            construction_map.insert(
+                decl.target_name_ident().to_string(),
+                default.to_token_stream(),
+            );
+        }
+    }
+    for def in opts_pair.__monomorphize_mod().constructions() {
+        construction_map.insert(
+            def.target_name_ident().to_string(),
+            def.construction().to_token_stream(),
+        );
+    }
+    construction_map
+}
+
+/// Removes the items named in the `skip` block from the top level of the
+/// template.
+fn skip_items(input_items: TokenStream, skip: &[Ident]) -> Result<TokenStream, syn::Error> {
+    if skip.is_empty() {
+        return Ok(input_items);
+    }
+
+    let mut file: syn::File = syn::parse2(input_items)?;
+    let mut err: Option<syn::Error> = None;
+    for name in skip {
+        let len_before = file.items.len();
+        file.items
+            .retain(|item| item_name_ident(item) != Some(name));
+        if file.items.len() == len_before {
+            let new_err = syn::Error::new(
+                name.span(),
+                format!("no item named `{}` in the template to skip", name),
+            );
+            match err {
+                Some(ref mut err) => err.combine(new_err),
+                None => err = Some(new_err),
+            }
+        }
+    }
+
+    match err {
+        Some(err) => Err(err),
+        None => Ok(file.into_token_stream()),
+    }
+}
+
+/// Replaces the bodies of the top-level functions of the template with the ones
+/// in the `overrides` block. The attributes of the functions in the template are
+/// kept, so that they are still processed as usual.
+fn override_items(
+    input_items: TokenStream,
+    overrides: &[syn::ItemFn],
+) -> Result<TokenStream, syn::Error> {
+    if overrides.is_empty() {
+        return Ok(input_items);
+    }
+
+    let mut file: syn::File = syn::parse2(input_items)?;
+    let mut err: Option<syn::Error> = None;
+    let mut previous_names = HashSet::new();
+    for overriding in overrides {
+        let name = &overriding.sig.ident;
+        let new_err = if let Some(attr) = overriding.attrs.first() {
+            Some(syn::Error::new(
+                attr.span(),
+                "attributes are not allowed on overriding functions. Those of the function in the template are kept",
+            ))
+        } else if !previous_names.insert(name.to_string()) {
+            Some(syn::Error::new(
+                name.span(),
+                format!("duplicate override of `{}`", name),
+            ))
+        } else {
+            let overridden = file.items.iter_mut().find_map(|item| match item {
+                syn::Item::Fn(item_fn) if item_fn.sig.ident == *name => Some(item_fn),
+                _ => None,
+            });
+            match overridden {
+                None => Some(syn::Error::new(
+                    name.span(),
+                    format!(
+                        "no top-level function named `{}` in the template to override",
+                        name
+                    ),
+                )),
+                Some(overridden)
+                    if normalized_signature(&overridden.sig)
+                        != normalized_signature(&overriding.sig) =>
+                {
+                    Some(syn::Error::new(
+                        overriding.sig.span(),
+                        format!(
+                            "the signature doesn't match the one of `{}` in the template: `{}`",
+                            name,
+                            overridden.sig.to_token_stream(),
+                        ),
+                    ))
+                }
+                Some(overridden) => {
+                    // NOTE: the template is pasted from the body of the macro
+                    // defined by `mod_template::define`, so the variables
+                    // bound in the template (e.g. by `__CONSTRUCT`) are
+                    // invisible to the code written in the macro call due to
+                    // hygiene, unless the latter is resolved at the former.
+                    let block = resolved_at(
+                        overriding.block.to_token_stream(),
+                        overridden.sig.ident.span(),
+                    );
+                    overridden.block = syn::parse2(block)?;
+                    None
+                }
+            }
+        };
+        if let Some(new_err) = new_err {
+            match err {
+                Some(ref mut err) => err.combine(new_err),
+                None => err = Some(new_err),
+            }
+        }
+    }
+
+    match err {
+        Some(err) => Err(err),
+        None => Ok(file.into_token_stream()),
+    }
+}
+
+/// Removes the trailing punctuation in the signature (e.g. `fn f(x: i32,)`),
+/// which doesn't change its meaning. Trailing commas of tuple types are kept,
+/// since they do, e.g. `(i32,)`.
+fn normalized_signature(sig: &syn::Signature) -> syn::Signature {
+    struct Normalizer;
+    impl VisitMut for Normalizer {
+        fn visit_signature_mut(&mut self, i: &mut syn::Signature) {
+            i.inputs.pop_punct();
+            visit_mut::visit_signature_mut(self, i);
+        }
+        fn visit_generics_mut(&mut self, i: &mut syn::Generics) {
+            i.params.pop_punct();
+            visit_mut::visit_generics_mut(self, i);
+        }
+        fn visit_where_clause_mut(&mut self, i: &mut syn::WhereClause) {
+            i.predicates.pop_punct();
+            visit_mut::visit_where_clause_mut(self, i);
+        }
+        fn visit_angle_bracketed_generic_arguments_mut(
+            &mut self,
+            i: &mut syn::AngleBracketedGenericArguments,
+        ) {
+            i.args.pop_punct();
+            visit_mut::visit_angle_bracketed_generic_arguments_mut(self, i);
+        }
+        fn visit_parenthesized_generic_arguments_mut(
+            &mut self,
+            i: &mut syn::ParenthesizedGenericArguments,
+        ) {
+            i.inputs.pop_punct();
+            visit_mut::visit_parenthesized_generic_arguments_mut(self, i);
+        }
+        fn visit_type_bare_fn_mut(&mut self, i: &mut syn::TypeBareFn) {
+            i.inputs.pop_punct();
+            visit_mut::visit_type_bare_fn_mut(self, i);
+        }
+    }
+
+    let mut sig = sig.clone();
+    Normalizer.visit_signature_mut(&mut sig);
+    sig
+}
+
+fn unknown_target_name_error(target_name_ident: &Ident, which: &str) -> syn::Error {
+    syn::Error::new(
+        target_name_ident.span(),
+        format!(
+            "unknown target name `{}`. It should be declared in the `{}` block {}",
+            target_name_ident, which, "among the options of the attribute `mod_template::define`"
+        ),
+    )
+}
+
+fn resolved_at(input: TokenStream, span: Span) -> TokenStream {
+    input
+        .into_iter()
+        .map(|mut tt| {
+            if let TokenTree::Group(ref group) = tt {
+                let mut new_group =
+                    Group::new(group.delimiter(), resolved_at(group.stream(), span));
+                new_group.set_span(group.span().resolved_at(span));
+                tt = TokenTree::Group(new_group);
+            } else {
+                tt.set_span(tt.span().resolved_at(span));
+            }
+            tt
+        })
+        .collect()
+}
+
+/// Type aliases and consts that make the types and consts defined in the
+/// `types` and `consts` blocks nameable in the module and its inline
+/// submodules.
+fn make_parameter_items(opts_pair: &AttributeOptionsPair) -> TokenStream {
+    let opts = opts_pair.__monomorphize_mod();
+
+    let mut output = TokenStream::new();
+    for def in opts.types() {
+        let target_name_ident = def.target_name_ident();
+        let ty = def.ty();
+        quote::quote!(
+            #[allow(dead_code)]
+            type #target_name_ident = #ty;
+        )
+        .to_tokens(&mut output);
+    }
+    for decl in opts_pair.define().consts() {
+        let target_name_ident = decl.target_name_ident();
+        let ty = decl.ty();
+        let def = opts
+            .consts()
+            .iter()
+            .find(|x| *x.target_name_ident() == *target_name_ident)
+            .expect(EXPECT_AVAILABLE);
+        let value = def.value();
+        quote::quote!(
+            #[allow(dead_code)]
+            const #target_name_ident: #ty = #value;
+        )
+        .to_tokens(&mut output);
+    }
+    output
+}
+
+/// Type aliases don't check bounds by themselves, so the bounds declared in
+/// the `types` block are checked here explicitly.
+fn make_type_bound_checks(opts_pair: &AttributeOptionsPair) -> TokenStream {
+    let mut output = TokenStream::new();
+    for decl in opts_pair.define().types() {
+        let bounds = decl.bounds();
+        if bounds.is_empty() {
+            continue;
+        }
+        let def = opts_pair
+            .__monomorphize_mod()
+            .types()
+            .iter()
+            .find(|x| *x.target_name_ident() == *decl.target_name_ident())
+            .expect(EXPECT_AVAILABLE);
+        let ty = def.ty();
+        quote::quote_spanned!(ty.span()=>
+            const _: () = {
+                fn assert_bounds<T: #bounds>() {}
+                let _ = assert_bounds::<#ty>;
+            };
+        )
+        .to_tokens(&mut output);
+    }
+    output
 }
 
 #[cfg(test)]
@@ -168,4 +892,753 @@ mod tests {
 
         assert_eq!(actual.to_string(), expected.to_string());
     }
+
+    #[test]
+    fn fn_template() {
+        let input_attr = quote::quote!(
+            (macro_rules! macro_name; constructions(CONS -> ToCons), types(Foo)),
+            {
+                #[an_attr]
+                pub fn an_fn;
+                constructions {
+                    CONS => new_something(),
+                },
+                types {
+                    Foo => crate::Foo,
+                },
+            }
+        );
+        let input_item = quote::quote! {
+            #[__CONSTRUCT(to_construct as CONS)]
+            fn __() {
+                let _: Foo = to_construct.foo();
+            }
+        };
+
+        let expected = quote::quote! {
+            #[an_attr]
+            #[::mod_template::construct(to_construct: ToCons = new_something())]
+            pub fn an_fn() {
+                #[allow(dead_code)]
+                type Foo = crate::Foo;
+                let _: Foo = to_construct.foo();
+            }
+        };
+
+        let actual = __monomorphize_mod(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn impl_template() {
+        let input_attr = quote::quote!(
+            (macro_rules! macro_name; constructions(CONS -> ToCons), consts(N: usize)),
+            {
+                impl<'a, T> crate::Foo<'a, T> where T: Clone;
+                constructions {
+                    CONS => new_something(),
+                },
+                consts {
+                    N => 1,
+                },
+            }
+        );
+        let input_item = quote::quote! {
+            impl<U> crate::Trait<U> for __ {
+                #[__CONSTRUCT(to_construct as CONS)]
+                fn an_fn(&self) -> usize {
+                    N
+                }
+            }
+        };
+
+        let expected = quote::quote! {
+            impl<'a, T, U> crate::Trait<U> for crate::Foo<'a, T> where T: Clone {
+                #[::mod_template::construct(to_construct: ToCons = new_something())]
+                fn an_fn(&self) -> usize {
+                    #[allow(dead_code)]
+                    const N: usize = 1;
+                    N
+                }
+            }
+        };
+
+        let actual = __monomorphize_mod(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn inline() {
+        let input_attr = quote::quote!(
+            (macro_rules! macro_name; constructions(CONS -> ToCons)),
+            {
+                mod _;
+                constructions {
+                    CONS => new_something(),
+                },
+                items {
+                    fn another_fn() {}
+                },
+            }
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                use crate::{Foo, Bar as Baz};
+
+                #[__CONSTRUCT(to_construct as CONS)]
+                fn an_fn() {}
+            }
+        };
+
+        let expected = quote::quote! {
+            use crate::{Foo, Bar as Baz};
+
+            #[::mod_template::construct(to_construct: ToCons = new_something())]
+            fn an_fn() {}
+            fn another_fn() {}
+
+            #[doc(hidden)]
+            #[allow(dead_code, non_camel_case_types)]
+            struct __mod_template__inlined__Baz;
+            #[doc(hidden)]
+            #[allow(dead_code, non_camel_case_types)]
+            struct __mod_template__inlined__Foo;
+            #[doc(hidden)]
+            #[allow(dead_code, non_camel_case_types)]
+            struct __mod_template__inlined__an_fn;
+            #[doc(hidden)]
+            #[allow(dead_code, non_camel_case_types)]
+            struct __mod_template__inlined__another_fn;
+        };
+
+        let actual = __monomorphize_mod(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn inline_types_and_consts() {
+        let input_attr = quote::quote!(
+            (macro_rules! macro_name; types(Foo), consts(BAR: usize)),
+            {
+                mod _;
+                types {
+                    Foo => crate::Foo,
+                },
+                consts {
+                    BAR => 42,
+                },
+            }
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                fn an_fn() {
+                    let _: [Foo; BAR];
+                }
+
+                mod a_sub_mod {}
+            }
+        };
+
+        let expected = quote::quote! {
+            fn an_fn() {
+                #[allow(dead_code)]
+                type Foo = crate::Foo;
+                #[allow(dead_code)]
+                const BAR: usize = 42;
+                let _: [Foo; BAR];
+            }
+            mod a_sub_mod {
+                #[allow(dead_code)]
+                type Foo = crate::Foo;
+                #[allow(dead_code)]
+                const BAR: usize = 42;
+            }
+
+            #[doc(hidden)]
+            #[allow(dead_code, non_camel_case_types)]
+            struct __mod_template__inlined__a_sub_mod;
+            #[doc(hidden)]
+            #[allow(dead_code, non_camel_case_types)]
+            struct __mod_template__inlined__an_fn;
+        };
+
+        let actual = __monomorphize_mod(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn inline_types_outside_bodies() {
+        let input_attr = quote::quote!(
+            (macro_rules! macro_name; types(Foo)),
+            {
+                mod _;
+                types {
+                    Foo => crate::Foo,
+                },
+            }
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                fn an_fn(_: Foo) {}
+            }
+        };
+
+        let expected = quote::quote! {
+            ::core::compile_error! { "`Foo` can only be used in the bodies of functions and in nested modules of an inline instance (`mod _;`)" }
+        };
+
+        let actual = __monomorphize_mod(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn types() {
+        let input_attr = quote::quote!(
+            (macro_rules! macro_name; types(Foo: Trait + Send, Bar)),
+            {
+                mod a_mod;
+                types {
+                    Foo => crate::Foo,
+                    Bar => Vec<crate::Bar>,
+                },
+            }
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                fn an_fn(foo: Foo) {}
+
+                mod a_sub_mod {
+                    fn another_fn(bar: Bar) {}
+                }
+            }
+        };
+
+        let expected = quote::quote! {
+            mod a_mod {
+                #[allow(dead_code)]
+                type Foo = crate::Foo;
+                #[allow(dead_code)]
+                type Bar = Vec<crate::Bar>;
+                fn an_fn(foo: Foo) {}
+
+                mod a_sub_mod {
+                    #[allow(dead_code)]
+                    type Foo = crate::Foo;
+                    #[allow(dead_code)]
+                    type Bar = Vec<crate::Bar>;
+                    fn another_fn(bar: Bar) {}
+                }
+
+                const _: () = {
+                    fn assert_bounds<T: Trait + Send>() {}
+                    let _ = assert_bounds::<crate::Foo>;
+                };
+            }
+        };
+
+        let actual = __monomorphize_mod(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn consts() {
+        let input_attr = quote::quote!(
+            (macro_rules! macro_name; consts(FOO: usize)),
+            {
+                mod a_mod;
+                consts {
+                    FOO => 42,
+                },
+            }
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                static BUFFER: [u8; FOO] = [0; FOO];
+
+                mod a_sub_mod {}
+            }
+        };
+
+        let expected = quote::quote! {
+            mod a_mod {
+                #[allow(dead_code)]
+                const FOO: usize = 42;
+                static BUFFER: [u8; FOO] = [0; FOO];
+
+                mod a_sub_mod {
+                    #[allow(dead_code)]
+                    const FOO: usize = 42;
+                }
+            }
+        };
+
+        let actual = __monomorphize_mod(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn idents() {
+        let input_attr = quote::quote!(
+            (macro_rules! macro_name; idents(FOO)),
+            {
+                mod a_mod;
+                idents {
+                    FOO => foo,
+                },
+            }
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                #[__IDENT(FOO)]
+                fn foo() {}
+
+                fn an_fn() {
+                    __IDENT(FOO)();
+                }
+            }
+        };
+
+        let expected = quote::quote! {
+            mod a_mod {
+                fn foo() {}
+
+                fn an_fn() {
+                    foo();
+                }
+            }
+        };
+
+        let actual = __monomorphize_mod(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn items() {
+        let input_attr = quote::quote!(
+            (macro_rules! macro_name; consts(FOO: i32)),
+            {
+                mod a_mod;
+                consts {
+                    FOO => 42,
+                },
+                items {
+                    #[test]
+                    fn extra() {
+                        assert_eq!(foo(), 42);
+                    }
+                },
+            }
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                fn foo() -> i32 {
+                    FOO
+                }
+            }
+        };
+
+        let expected = quote::quote! {
+            mod a_mod {
+                #[allow(dead_code)]
+                const FOO: i32 = 42;
+
+                fn foo() -> i32 {
+                    FOO
+                }
+
+                #[test]
+                fn extra() {
+                    assert_eq!(foo(), 42);
+                }
+            }
+        };
+
+        let actual = __monomorphize_mod(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn skip() {
+        let input_attr = quote::quote!(
+            (macro_rules! macro_name;),
+            {
+                mod a_mod;
+                skip { foo, Bar },
+            }
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                fn foo() {}
+
+                struct Bar;
+
+                fn baz() {}
+            }
+        };
+
+        let expected = quote::quote! {
+            mod a_mod {
+                fn baz() {}
+            }
+        };
+
+        let actual = __monomorphize_mod(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn skip_unknown_item() {
+        let input_attr = quote::quote!(
+            (macro_rules! macro_name;),
+            {
+                mod a_mod;
+                skip { qux },
+            }
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                fn foo() {}
+            }
+        };
+
+        let expected = quote::quote! {
+            ::core::compile_error! { "no item named `qux` in the template to skip" }
+        };
+
+        let actual = __monomorphize_mod(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn overrides() {
+        let input_attr = quote::quote!(
+            (macro_rules! macro_name; constructions(FOO -> i32)),
+            {
+                mod a_mod;
+                constructions {
+                    FOO => 42,
+                },
+                overrides {
+                    fn bar() {
+                        assert_eq!(foo, 42);
+                    }
+                },
+            }
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                #[__CONSTRUCT(foo as FOO)]
+                fn bar() {
+                    assert_eq!(foo, 24);
+                }
+            }
+        };
+
+        let expected = quote::quote! {
+            mod a_mod {
+                #[::mod_template::construct(foo: i32 = 42)]
+                fn bar() {
+                    assert_eq!(foo, 42);
+                }
+            }
+        };
+
+        let actual = __monomorphize_mod(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn overrides_with_trailing_punctuation() {
+        let input_attr = quote::quote!(
+            (macro_rules! macro_name;),
+            {
+                mod a_mod;
+                overrides {
+                    fn bar<T: Into<i32,>,>(x: T, y: (i32,),) -> i32 where T: Copy, {
+                        x.into() + y.0
+                    }
+                },
+            }
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                fn bar<T: Into<i32>>(x: T, y: (i32,)) -> i32 where T: Copy {
+                    0
+                }
+            }
+        };
+
+        let expected = quote::quote! {
+            mod a_mod {
+                fn bar<T: Into<i32> >(x: T, y: (i32,)) -> i32 where T: Copy {
+                    x.into() + y.0
+                }
+            }
+        };
+
+        let actual = __monomorphize_mod(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn overrides_with_mismatched_signature() {
+        let input_attr = quote::quote!(
+            (macro_rules! macro_name;),
+            {
+                mod a_mod;
+                overrides {
+                    fn bar() -> i32 {
+                        42
+                    }
+                },
+            }
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                fn bar() {}
+            }
+        };
+
+        let expected = quote::quote! {
+            ::core::compile_error! { "the signature doesn't match the one of `bar` in the template: `fn bar ()`" }
+        };
+
+        let actual = __monomorphize_mod(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn construction_defaults() {
+        let input_attr = quote::quote!(
+            (macro_rules! macro_name; constructions(FOO -> i32 = 24, BAR -> i32 = 42)),
+            {
+                mod a_mod;
+                constructions {
+                    FOO => 42,
+                },
+            }
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                #[__CONSTRUCT(foo as FOO, bar as BAR)]
+                fn baz() {
+                    assert_eq!(foo, bar);
+                }
+            }
+        };
+
+        let expected = quote::quote! {
+            mod a_mod {
+                #[::mod_template::construct(foo: i32 = 42)]
+                #[::mod_template::construct(bar: i32 = 42)]
+                fn baz() {
+                    assert_eq!(foo, bar);
+                }
+            }
+        };
+
+        let actual = __monomorphize_mod(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn attribute_substitution_defaults() {
+        let input_attr = quote::quote!(
+            (macro_rules! macro_name; attribute_substitutions(FOO = #[foo], BAR = #[bar], BAZ?)),
+            {
+                mod a_mod;
+                attribute_substitutions {
+                    BAR => #[baz],
+                },
+            }
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                #[__SUBSTITUTE(FOO)]
+                #[__SUBSTITUTE(BAR)]
+                #[__SUBSTITUTE(BAZ)]
+                fn qux() {}
+            }
+        };
+
+        let expected = quote::quote! {
+            mod a_mod {
+                #[foo]
+                #[baz]
+                fn qux() {}
+            }
+        };
+
+        let actual = __monomorphize_mod(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn declared_parameter_list_extensions() {
+        let input_attr = quote::quote!(
+            (macro_rules! macro_name; attribute_substitutions(FOO(.., n: i32), BAR(.., n: i32))),
+            {
+                mod a_mod;
+                attribute_substitutions {
+                    FOO => #[foo],
+                    BAR => #[bar] (.., n: i32, m: i32),
+                },
+            }
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                #[__SUBSTITUTE(FOO)]
+                fn baz() {}
+
+                #[__SUBSTITUTE(BAR)]
+                fn qux() {}
+            }
+        };
+
+        let expected = quote::quote! {
+            mod a_mod {
+                #[::mod_template::extend_parameter_list(.., n: i32)]
+                #[foo]
+                fn baz() {}
+
+                #[::mod_template::extend_parameter_list(.., n: i32, m: i32)]
+                #[bar]
+                fn qux() {}
+            }
+        };
+
+        let actual = __monomorphize_mod(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn mismatched_parameter_list_extensions() {
+        let input_attr = quote::quote!(
+            (macro_rules! macro_name; attribute_substitutions(FOO(.., n: i32))),
+            {
+                mod a_mod;
+                attribute_substitutions {
+                    FOO => #[foo] (.., m: i32),
+                },
+            }
+        );
+        let input_item = quote::quote! {
+            mod __ {}
+        };
+
+        let expected = quote::quote! {
+            ::core::compile_error! { "the parameter list extension of `FOO` should start with the declared parameters `n : i32`" }
+        };
+
+        let actual = __monomorphize_mod(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn field_list_extensions() {
+        let input_attr = quote::quote!(
+            (macro_rules! macro_name; attribute_substitutions(FOO?, BAR?)),
+            {
+                mod a_mod;
+                attribute_substitutions {
+                    FOO => #[derive(Debug)] { pub extra: Extra },
+                    BAR => { Extra },
+                },
+            }
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                #[__SUBSTITUTE(FOO)]
+                struct Baz {
+                    value: i32,
+                }
+
+                #[__SUBSTITUTE(BAR)]
+                struct Qux(i32);
+            }
+        };
+
+        let expected = quote::quote! {
+            mod a_mod {
+                #[::mod_template::extend_field_list(.., pub extra: Extra)]
+                #[derive(Debug)]
+                struct Baz {
+                    value: i32,
+                }
+
+                #[::mod_template::extend_field_list(.., Extra)]
+                struct Qux(i32);
+            }
+        };
+
+        let actual = __monomorphize_mod(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn nested_instances() {
+        let input_attr = quote::quote!(
+            (macro_rules! macro_name; constructions(CONS -> ToCons), attribute_substitutions(ATTR_SUBST)),
+            {
+                mod a_mod;
+                constructions {
+                    CONS => new_something(),
+                },
+                attribute_substitutions {
+                    ATTR_SUBST => #[an_attr],
+                },
+            }
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                #[__NESTED]
+                define_another_mod! {
+                    mod a_nested_mod;
+                    constructions { OTHER_CONS => __FORWARD(CONS) },
+                    attribute_substitutions { OTHER_ATTR_SUBST => __FORWARD(ATTR_SUBST) },
+                    items {
+                        #[__SUBSTITUTE(ATTR_SUBST)]
+                        fn an_fn() {}
+                    },
+                }
+            }
+        };
+
+        let expected = quote::quote! {
+            mod a_mod {
+                define_another_mod! {
+                    mod a_nested_mod;
+                    constructions { OTHER_CONS => new_something() },
+                    attribute_substitutions { OTHER_ATTR_SUBST => #[an_attr] (..) },
+                    items {
+                        #[__SUBSTITUTE(ATTR_SUBST)]
+                        fn an_fn() {}
+                    },
+                }
+            }
+        };
+
+        let actual = __monomorphize_mod(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
 }
@@ -0,0 +1,310 @@
+use std::collections::HashSet;
+
+use proc_macro2::{Delimiter, Group, Punct, Spacing, TokenStream, TokenTree};
+use quote::TokenStreamExt;
+
+use super::matrix::{merge_blocks, parse_blocks, Block};
+
+/// The blocks consisting of definitions like `NAME => …`.
+const KEYED_BLOCK_NAMES: &[&str] = &[
+    "constructions",
+    "attribute_substitutions",
+    "types",
+    "consts",
+    "idents",
+];
+
+/// Expands the `mods` block in the options of the attribute
+/// `__monomorphize_mod` (the part in braces), if any, into the options of every
+/// module listed in it, with the blocks outside the `mods` block shared among
+/// them. The definitions in the shared blocks are defaults, which are replaced
+/// by the ones for the same target names in the blocks of each module.
+///
+/// In this form, the options start with blocks directly, rather than the
+/// module header.
+pub fn expand(attr: TokenStream) -> syn::Result<Vec<TokenStream>> {
+    let tts: Vec<TokenTree> = attr.clone().into_iter().collect();
+    let [define_group @ TokenTree::Group(..), comma @ TokenTree::Punct(..), TokenTree::Group(instance_group)] =
+        &tts[..]
+    else {
+        // NOTE: let the parser of `AttributeOptionsPair` report errors.
+        return Ok(vec![attr]);
+    };
+
+    // NOTE: the usual options start with the module header, which can't be
+    // parsed as blocks, so they are left as they are unless there is a `mods`
+    // block.
+    if !has_mods_block(instance_group.stream()) {
+        return Ok(vec![attr]);
+    }
+    let mut blocks = parse_blocks(instance_group.stream())?;
+    let mods_index = blocks
+        .iter()
+        .position(|block| block.name_ident == "mods")
+        .expect("there should be a mods block");
+    let mods = blocks.remove(mods_index);
+    if blocks.iter().any(|block| block.name_ident == "mods") {
+        return Err(syn::Error::new(
+            mods.name_ident.span(),
+            "duplicate mods block",
+        ));
+    }
+
+    let mut output = vec![];
+    for (header, entry_content) in parse_entries(mods.content)? {
+        let entry_blocks = parse_blocks(entry_content)?;
+        let shared_blocks = without_redefined(&blocks, &entry_blocks);
+        let merged = merge_blocks(shared_blocks.iter().chain(&entry_blocks));
+
+        let mut new_instance_group = Group::new(Delimiter::Brace, quote::quote!(#header; #merged));
+        new_instance_group.set_span(instance_group.span());
+
+        let mut attr = TokenStream::new();
+        attr.append(define_group.clone());
+        attr.append(comma.clone());
+        attr.append(new_instance_group);
+        output.push(attr);
+    }
+
+    Ok(output)
+}
+
+/// Removes the definitions in the shared blocks whose target names are defined
+/// again in the blocks of an entry.
+fn without_redefined(shared_blocks: &[Block], entry_blocks: &[Block]) -> Vec<Block> {
+    shared_blocks
+        .iter()
+        .map(|block| {
+            if !KEYED_BLOCK_NAMES
+                .iter()
+                .any(|name| block.name_ident == name)
+            {
+                return block.clone();
+            }
+            let redefined: HashSet<String> = (entry_blocks.iter())
+                .filter(|entry_block| entry_block.name_ident == block.name_ident)
+                .flat_map(|entry_block| split_definitions(entry_block.content.clone()))
+                .filter_map(|(name_ident, _)| Some(name_ident?.to_string()))
+                .collect();
+
+            let mut content = TokenStream::new();
+            for (name_ident, definition) in split_definitions(block.content.clone()) {
+                if name_ident.is_some_and(|name_ident| redefined.contains(&name_ident.to_string()))
+                {
+                    continue;
+                }
+                if !content.is_empty() {
+                    content.append(Punct::new(',', Spacing::Alone));
+                }
+                content.extend(definition);
+            }
+            Block {
+                name_ident: block.name_ident.clone(),
+                content,
+            }
+        })
+        .collect()
+}
+
+/// Splits something like `FOO => …, BAR => …,` into the definitions, along
+/// with their target names.
+///
+/// NOTE: a definition may contain commas outside of groups (e.g.
+/// `Foo => crate::Pair<A, B>`), so only a comma followed by something like
+/// `NAME =>` ends one.
+fn split_definitions(content: TokenStream) -> Vec<(Option<proc_macro2::Ident>, TokenStream)> {
+    let tts: Vec<TokenTree> = content.into_iter().collect();
+    let starts_definition = |index: usize| {
+        matches!(
+            tts.get(index..index + 3),
+            Some([TokenTree::Ident(_), TokenTree::Punct(eq), TokenTree::Punct(gt)])
+                if eq.as_char() == '=' && eq.spacing() == Spacing::Joint && gt.as_char() == '>'
+        )
+    };
+
+    let mut definitions = vec![];
+    let mut definition: Vec<TokenTree> = vec![];
+    for (index, tt) in tts.iter().enumerate() {
+        let is_separator = matches!(tt, TokenTree::Punct(punct) if punct.as_char() == ',')
+            && (index + 1 == tts.len() || starts_definition(index + 1));
+        if !is_separator {
+            definition.push(tt.clone());
+            continue;
+        }
+        definitions.push(std::mem::take(&mut definition));
+    }
+    if !definition.is_empty() {
+        definitions.push(definition);
+    }
+
+    definitions
+        .into_iter()
+        .map(|definition| {
+            let name_ident = match definition.first() {
+                Some(TokenTree::Ident(ident)) => Some(ident.clone()),
+                _ => None,
+            };
+            (name_ident, definition.into_iter().collect())
+        })
+        .collect()
+}
+
+/// Whether there is something like `mods { … }` at the top level of the
+/// options.
+fn has_mods_block(input: TokenStream) -> bool {
+    let tts: Vec<TokenTree> = input.into_iter().collect();
+    tts.windows(2).any(|pair| {
+        matches!(
+            pair,
+            [TokenTree::Ident(ident), TokenTree::Group(group)]
+                if ident == "mods" && group.delimiter() == Delimiter::Brace
+        )
+    })
+}
+
+/// Parses something like `mod foo => { blocks… }, pub mod bar => { … }, …`.
+fn parse_entries(input: TokenStream) -> syn::Result<Vec<(TokenStream, TokenStream)>> {
+    let mut entries = vec![];
+    let mut iter = input.into_iter().peekable();
+    while iter.peek().is_some() {
+        let mut header = TokenStream::new();
+        loop {
+            match iter.next() {
+                Some(TokenTree::Punct(eq)) if eq.as_char() == '=' => match iter.next() {
+                    Some(TokenTree::Punct(gt)) if gt.as_char() == '>' => break,
+                    _ => return Err(syn::Error::new(eq.span(), "expected `=>`")),
+                },
+                Some(tt) => header.append(tt),
+                None => {
+                    return Err(syn::Error::new(
+                        proc_macro2::Span::call_site(),
+                        "expected `=>` after the module header in the mods block",
+                    ))
+                }
+            }
+        }
+        let content = match iter.next() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                group.stream()
+            }
+            Some(tt) => return Err(syn::Error::new(tt.span(), "expected blocks in braces")),
+            None => {
+                return Err(syn::Error::new(
+                    proc_macro2::Span::call_site(),
+                    "expected blocks in braces after `=>` in the mods block",
+                ))
+            }
+        };
+        entries.push((header, content));
+        match iter.next() {
+            None => {}
+            Some(TokenTree::Punct(punct)) if punct.as_char() == ',' => {}
+            Some(tt) => return Err(syn::Error::new(tt.span(), "expected `,`")),
+        }
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand;
+
+    #[test]
+    fn basic() {
+        let input = quote::quote!(
+            (macro_rules! macro_name; constructions(FOO -> i32)),
+            {
+                attribute_substitutions { BAR => #[bar] },
+                mods {
+                    mod one => { constructions { FOO => 1 } },
+                    #[an_attr]
+                    pub mod two => { constructions { FOO => 2 }, skip { baz } },
+                },
+            }
+        );
+
+        let expected = [
+            quote::quote!(
+                (macro_rules! macro_name; constructions(FOO -> i32)),
+                {
+                    mod one;
+                    attribute_substitutions { BAR => #[bar] },
+                    constructions { FOO => 1 },
+                }
+            ),
+            quote::quote!(
+                (macro_rules! macro_name; constructions(FOO -> i32)),
+                {
+                    #[an_attr]
+                    pub mod two;
+                    attribute_substitutions { BAR => #[bar] },
+                    constructions { FOO => 2 },
+                    skip { baz },
+                }
+            ),
+        ]
+        .map(|x| x.to_string());
+
+        let actual = expand(input).unwrap();
+        let actual: Vec<_> = actual.into_iter().map(|x| x.to_string()).collect();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn redefinitions() {
+        let input = quote::quote!(
+            (macro_rules! macro_name; constructions(FOO -> i32, BAR -> i32), types(Baz: Qux)),
+            {
+                constructions { FOO => 1, BAR => 2 },
+                types { Baz => crate::Pair<A, B> },
+                mods {
+                    mod one => { constructions { BAR => 3 }, types { Baz => crate::Quux } },
+                    mod two => {},
+                },
+            }
+        );
+
+        let expected = [
+            quote::quote!(
+                (macro_rules! macro_name; constructions(FOO -> i32, BAR -> i32), types(Baz: Qux)),
+                {
+                    mod one;
+                    constructions { FOO => 1, BAR => 3 },
+                    types { Baz => crate::Quux },
+                }
+            ),
+            quote::quote!(
+                (macro_rules! macro_name; constructions(FOO -> i32, BAR -> i32), types(Baz: Qux)),
+                {
+                    mod two;
+                    constructions { FOO => 1, BAR => 2 },
+                    types { Baz => crate::Pair<A, B> },
+                }
+            ),
+        ]
+        .map(|x| x.to_string());
+
+        let actual = expand(input).unwrap();
+        let actual: Vec<_> = actual.into_iter().map(|x| x.to_string()).collect();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn malformed_blocks() {
+        let input = quote::quote!(
+            (macro_rules! macro_name; constructions(FOO -> i32)),
+            {
+                attribute_substitutions { BAR => #[bar] }
+                mods {
+                    mod one => { constructions { FOO => 1 } },
+                },
+            }
+        );
+
+        let err = expand(input).unwrap_err();
+
+        assert_eq!(err.to_string(), "expected `,`");
+    }
+}
@@ -1,13 +1,25 @@
 use std::collections::HashSet;
 
+use syn::parse::Parse;
+
+use crate::attributes::extend_parameter_list;
+
 pub struct AttributeSubstitutionDeclaration {
     target_name_ident: syn::Ident,
+    parameter_list_extension: Option<extend_parameter_list::AttributeOptions>,
+    default: Option<Vec<syn::Attribute>>,
 }
 
 impl AttributeSubstitutionDeclaration {
     pub fn target_name_ident(&self) -> &syn::Ident {
         &self.target_name_ident
     }
+    pub fn parameter_list_extension(&self) -> &Option<extend_parameter_list::AttributeOptions> {
+        &self.parameter_list_extension
+    }
+    pub fn default(&self) -> Option<&Vec<syn::Attribute>> {
+        self.default.as_ref()
+    }
 }
 
 pub fn parse(input: syn::parse::ParseStream) -> syn::Result<Vec<AttributeSubstitutionDeclaration>> {
@@ -15,8 +27,39 @@ pub fn parse(input: syn::parse::ParseStream) -> syn::Result<Vec<AttributeSubstit
         input: syn::parse::ParseStream,
     ) -> syn::Result<AttributeSubstitutionDeclaration> {
         let target_name_ident: syn::Ident = input.parse()?;
+        let parameter_list_extension = if input.peek(syn::token::Paren) {
+            let content;
+            syn::parenthesized!(content in input);
+            Some(content.call(extend_parameter_list::AttributeOptions::parse)?)
+        } else {
+            None
+        };
+        let parameter_list_extension =
+            parameter_list_extension.and_then(|x| if x.is_noop() { None } else { Some(x) });
+        // NOTE: an optional attribute substitution is just one that defaults to
+        // no attributes.
+        let default = if input.peek(syn::Token![?]) {
+            let _: syn::Token![?] = input.parse()?;
+            Some(vec![])
+        } else if input.peek(syn::Token![=]) {
+            let eq_token: syn::Token![=] = input.parse()?;
+            let new_attributes = input.call(syn::Attribute::parse_outer)?;
+            if new_attributes.is_empty() {
+                return Err(syn::Error::new(
+                    eq_token.span,
+                    "expected attributes as the default substitution",
+                ));
+            }
+            Some(new_attributes)
+        } else {
+            None
+        };
 
-        Ok(AttributeSubstitutionDeclaration { target_name_ident })
+        Ok(AttributeSubstitutionDeclaration {
+            target_name_ident,
+            parameter_list_extension,
+            default,
+        })
     }
 
     let vec: Vec<_> = {
@@ -30,7 +73,10 @@ pub fn parse(input: syn::parse::ParseStream) -> syn::Result<Vec<AttributeSubstit
 
     {
         let mut previous_names = HashSet::new();
-        for AttributeSubstitutionDeclaration { target_name_ident } in &vec {
+        for AttributeSubstitutionDeclaration {
+            target_name_ident, ..
+        } in &vec
+        {
             let target_name = target_name_ident.to_string();
             if previous_names.contains(&target_name) {
                 return Err(syn::Error::new(
@@ -47,18 +93,37 @@ pub fn parse(input: syn::parse::ParseStream) -> syn::Result<Vec<AttributeSubstit
 
 #[cfg(test)]
 pub(crate) mod tests {
+    use quote::ToTokens;
+
+    use crate::attributes::extend_parameter_list;
+
     use super::AttributeSubstitutionDeclaration;
 
     #[derive(Debug, PartialEq, Eq, typed_builder::TypedBuilder)]
     pub struct AttributeSubstitutionDeclarationForTest {
         target_name: String,
+        #[builder(default)]
+        parameter_list_extension: Option<extend_parameter_list::tests::AttributeOptionsForTest>,
+        #[builder(default)]
+        default: Option<Vec<String>>,
     }
     impl From<AttributeSubstitutionDeclaration> for AttributeSubstitutionDeclarationForTest {
         fn from(value: AttributeSubstitutionDeclaration) -> Self {
-            let AttributeSubstitutionDeclaration { target_name_ident } = value;
+            let AttributeSubstitutionDeclaration {
+                target_name_ident,
+                parameter_list_extension,
+                default,
+            } = value;
 
             Self {
                 target_name: target_name_ident.to_string(),
+                parameter_list_extension: parameter_list_extension.map(|x| x.into()),
+                default: default.map(|attrs| {
+                    attrs
+                        .into_iter()
+                        .map(|attr| attr.into_token_stream().to_string())
+                        .collect()
+                }),
             }
         }
     }
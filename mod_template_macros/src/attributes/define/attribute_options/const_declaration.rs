@@ -0,0 +1,82 @@
+use std::collections::HashSet;
+
+pub struct ConstDeclaration {
+    target_name_ident: syn::Ident,
+    ty: syn::Type,
+}
+
+impl ConstDeclaration {
+    pub fn target_name_ident(&self) -> &syn::Ident {
+        &self.target_name_ident
+    }
+    pub fn ty(&self) -> &syn::Type {
+        &self.ty
+    }
+}
+
+pub fn parse(input: syn::parse::ParseStream) -> syn::Result<Vec<ConstDeclaration>> {
+    fn parse_param(input: syn::parse::ParseStream) -> syn::Result<ConstDeclaration> {
+        let target_name_ident: syn::Ident = input.parse()?;
+        let _: syn::Token![:] = input.parse()?;
+        let ty: syn::Type = input.parse()?;
+
+        Ok(ConstDeclaration {
+            target_name_ident,
+            ty,
+        })
+    }
+
+    let vec: Vec<_> = {
+        let content;
+        syn::parenthesized!(content in input);
+        content
+            .parse_terminated(parse_param, syn::Token![,])?
+            .into_iter()
+            .collect()
+    };
+
+    {
+        let mut previous_names = HashSet::new();
+        for ConstDeclaration {
+            target_name_ident, ..
+        } in &vec
+        {
+            let target_name = target_name_ident.to_string();
+            if previous_names.contains(&target_name) {
+                return Err(syn::Error::new(
+                    target_name_ident.span(),
+                    "duplicate target name",
+                ));
+            }
+            previous_names.insert(target_name);
+        }
+    }
+
+    Ok(vec)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use quote::ToTokens;
+
+    use super::ConstDeclaration;
+
+    #[derive(Debug, PartialEq, Eq, typed_builder::TypedBuilder)]
+    pub struct ConstDeclarationForTest {
+        target_name: String,
+        ty: String,
+    }
+    impl From<ConstDeclaration> for ConstDeclarationForTest {
+        fn from(value: ConstDeclaration) -> Self {
+            let ConstDeclaration {
+                target_name_ident,
+                ty,
+            } = value;
+
+            Self {
+                target_name: target_name_ident.to_string(),
+                ty: ty.into_token_stream().to_string(),
+            }
+        }
+    }
+}
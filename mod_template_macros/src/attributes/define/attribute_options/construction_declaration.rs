@@ -3,6 +3,7 @@ use std::collections::HashSet;
 pub struct ConstructionDeclaration {
     target_name_ident: syn::Ident,
     ty: syn::Type,
+    default: Option<syn::Expr>,
 }
 
 impl ConstructionDeclaration {
@@ -12,6 +13,9 @@ impl ConstructionDeclaration {
     pub fn ty(&self) -> &syn::Type {
         &self.ty
     }
+    pub fn default(&self) -> Option<&syn::Expr> {
+        self.default.as_ref()
+    }
 }
 
 pub fn parse(input: syn::parse::ParseStream) -> syn::Result<Vec<ConstructionDeclaration>> {
@@ -19,16 +23,17 @@ pub fn parse(input: syn::parse::ParseStream) -> syn::Result<Vec<ConstructionDecl
         let target_name_ident: syn::Ident = input.parse()?;
         let _: syn::Token![->] = input.parse()?;
         let ty: syn::Type = input.parse()?;
-        if let syn::Type::ImplTrait(syn::TypeImplTrait { impl_token, .. }) = ty {
-            return Err(syn::Error::new(
-                impl_token.span,
-                "`impl` types are unsupported for now",
-            ));
+        let default: Option<syn::Expr> = if input.peek(syn::Token![=]) {
+            let _: syn::Token![=] = input.parse()?;
+            Some(input.parse()?)
+        } else {
+            None
         };
 
         Ok(ConstructionDeclaration {
             target_name_ident,
             ty,
+            default,
         })
     }
 
@@ -71,17 +76,21 @@ pub(crate) mod tests {
     pub struct ConstructionDeclarationForTest {
         target_name: String,
         ty: String,
+        #[builder(default)]
+        default: Option<String>,
     }
     impl From<ConstructionDeclaration> for ConstructionDeclarationForTest {
         fn from(value: ConstructionDeclaration) -> Self {
             let ConstructionDeclaration {
                 target_name_ident,
                 ty,
+                default,
             } = value;
 
             Self {
                 target_name: target_name_ident.to_string(),
                 ty: ty.into_token_stream().to_string(),
+                default: default.map(|default| default.into_token_stream().to_string()),
             }
         }
     }
@@ -14,6 +14,11 @@ impl MbeHeader {
     pub fn name_ident(&self) -> &syn::Ident {
         &self.name_ident
     }
+    pub fn is_exported(&self) -> bool {
+        self.attributes
+            .iter()
+            .any(|attr| attr.path().is_ident("macro_export"))
+    }
 }
 
 impl syn::parse::Parse for MbeHeader {
@@ -1,29 +1,66 @@
 pub(crate) mod attribute_substitution_declaration;
+pub(crate) mod const_declaration;
 pub(crate) mod construction_declaration;
+pub(crate) mod ident_declaration;
 pub(crate) mod mbe_header;
+pub(crate) mod type_declaration;
 
 use std::collections::HashMap;
 
 pub use self::attribute_substitution_declaration::AttributeSubstitutionDeclaration;
+pub use self::const_declaration::ConstDeclaration;
 pub use self::construction_declaration::ConstructionDeclaration;
+pub use self::ident_declaration::IdentDeclaration;
 pub use self::mbe_header::MbeHeader;
+pub use self::type_declaration::TypeDeclaration;
 
 pub struct AttributeOptions {
     mbe_header: MbeHeader,
+    crate_path: Option<syn::Path>,
+    path: Option<syn::LitStr>,
+    extends: Option<syn::Path>,
     constructions: Vec<ConstructionDeclaration>,
     attribute_substitutions: Vec<AttributeSubstitutionDeclaration>,
+    types: Vec<TypeDeclaration>,
+    consts: Vec<ConstDeclaration>,
+    idents: Vec<IdentDeclaration>,
 }
 
 impl AttributeOptions {
     pub fn mbe_header(&self) -> &MbeHeader {
         &self.mbe_header
     }
+    /// The path to the crate `mod_template`, which can be changed by the
+    /// `crate` option, e.g. if the crate is re-exported by another one.
+    pub fn crate_path(&self) -> syn::Path {
+        match &self.crate_path {
+            Some(crate_path) => crate_path.clone(),
+            None => syn::parse_quote!(::mod_template),
+        }
+    }
+    /// The path of the file to load the content of the template from,
+    /// relative to the file where the template is defined.
+    pub fn path(&self) -> Option<&syn::LitStr> {
+        self.path.as_ref()
+    }
+    pub fn extends(&self) -> Option<&syn::Path> {
+        self.extends.as_ref()
+    }
     pub fn constructions(&self) -> &Vec<ConstructionDeclaration> {
         &self.constructions
     }
     pub fn attribute_substitutions(&self) -> &Vec<AttributeSubstitutionDeclaration> {
         &self.attribute_substitutions
     }
+    pub fn types(&self) -> &Vec<TypeDeclaration> {
+        &self.types
+    }
+    pub fn consts(&self) -> &Vec<ConstDeclaration> {
+        &self.consts
+    }
+    pub fn idents(&self) -> &Vec<IdentDeclaration> {
+        &self.idents
+    }
 
     pub fn build_type_map(&self) -> HashMap<String, syn::Type> {
         let mut type_map: HashMap<String, syn::Type> = HashMap::new();
@@ -35,6 +72,116 @@ impl AttributeOptions {
         }
         type_map
     }
+
+    /// Adds the declarations of a template extending this one. The header of
+    /// the macro, the `crate` option, the `path` option and the `extends` option
+    /// of the latter are ignored.
+    pub fn merge(&mut self, other: AttributeOptions) -> syn::Result<()> {
+        fn append<T>(
+            this: &mut Vec<T>,
+            other: Vec<T>,
+            which: &str,
+            target_name_ident: impl Fn(&T) -> &syn::Ident,
+            err: &mut Option<syn::Error>,
+        ) {
+            for decl in other {
+                let name_ident = target_name_ident(&decl);
+                if this.iter().any(|x| target_name_ident(x) == name_ident) {
+                    let new_err = syn::Error::new(
+                        name_ident.span(),
+                        format!(
+                            "duplicate target name `{}`. It is already declared in the `{}` block of the template being extended",
+                            name_ident, which
+                        ),
+                    );
+                    match err {
+                        Some(ref mut err) => err.combine(new_err),
+                        None => *err = Some(new_err),
+                    }
+                } else {
+                    this.push(decl);
+                }
+            }
+        }
+
+        let mut err: Option<syn::Error> = None;
+        append(
+            &mut self.constructions,
+            other.constructions,
+            "constructions",
+            |x| x.target_name_ident(),
+            &mut err,
+        );
+        append(
+            &mut self.attribute_substitutions,
+            other.attribute_substitutions,
+            "attribute_substitutions",
+            |x| x.target_name_ident(),
+            &mut err,
+        );
+        append(
+            &mut self.types,
+            other.types,
+            "types",
+            |x| x.target_name_ident(),
+            &mut err,
+        );
+        append(
+            &mut self.consts,
+            other.consts,
+            "consts",
+            |x| x.target_name_ident(),
+            &mut err,
+        );
+        append(
+            &mut self.idents,
+            other.idents,
+            "idents",
+            |x| x.target_name_ident(),
+            &mut err,
+        );
+
+        match err {
+            Some(err) => Err(err),
+            None => self.check_ident_clashes(),
+        }
+    }
+
+    /// Checks that no target name in the `idents` block is also declared in
+    /// the `constructions`, `types` or `consts` block, since they would be
+    /// ambiguous in the template.
+    fn check_ident_clashes(&self) -> syn::Result<()> {
+        let mut err: Option<syn::Error> = None;
+        for decl in self.idents() {
+            let name_ident = decl.target_name_ident();
+            let which =
+                if (self.constructions().iter()).any(|x| x.target_name_ident() == name_ident) {
+                    "constructions"
+                } else if (self.types().iter()).any(|x| x.target_name_ident() == name_ident) {
+                    "types"
+                } else if (self.consts().iter()).any(|x| x.target_name_ident() == name_ident) {
+                    "consts"
+                } else {
+                    continue;
+                };
+            let new_err = syn::Error::new(
+                name_ident.span(),
+                format!(
+                    "duplicate target name `{}`. It is already declared in the `{}` block",
+                    name_ident, which
+                ),
+            );
+            match err {
+                Some(ref mut err) => err.combine(new_err),
+                None => err = Some(new_err),
+            }
+        }
+
+        match err {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
 }
 
 impl syn::parse::Parse for AttributeOptions {
@@ -43,26 +190,77 @@ impl syn::parse::Parse for AttributeOptions {
         if input.is_empty() {
             return Ok(Self {
                 mbe_header,
+                crate_path: None,
+                path: None,
+                extends: None,
                 constructions: vec![],
                 attribute_substitutions: vec![],
+                types: vec![],
+                consts: vec![],
+                idents: vec![],
             });
         }
         let _: syn::Token![;] = input.parse()?;
 
+        let mut crate_path: Option<syn::Path> = None;
+        let mut path: Option<syn::LitStr> = None;
+        let mut extends: Option<syn::Path> = None;
         let mut constructions: Option<Vec<ConstructionDeclaration>> = None;
         let mut attribute_substitutions: Option<Vec<AttributeSubstitutionDeclaration>> = None;
+        let mut types: Option<Vec<TypeDeclaration>> = None;
+        let mut consts: Option<Vec<ConstDeclaration>> = None;
+        let mut idents: Option<Vec<IdentDeclaration>> = None;
 
         loop {
             if input.is_empty() {
-                return Ok(Self {
+                let opts = Self {
                     mbe_header,
+                    crate_path,
+                    path,
+                    extends,
                     constructions: constructions.unwrap_or_default(),
                     attribute_substitutions: attribute_substitutions.unwrap_or_default(),
-                });
+                    types: types.unwrap_or_default(),
+                    consts: consts.unwrap_or_default(),
+                    idents: idents.unwrap_or_default(),
+                };
+                opts.check_ident_clashes()?;
+                return Ok(opts);
+            }
+
+            if input.peek(syn::Token![crate]) {
+                let crate_token: syn::Token![crate] = input.parse()?;
+                if crate_path.is_some() {
+                    return Err(syn::Error::new(crate_token.span, "duplicate crate option"));
+                }
+                let _: syn::Token![=] = input.parse()?;
+                crate_path = Some(input.call(syn::Path::parse_mod_style)?);
+
+                if !input.is_empty() {
+                    let _: syn::Token![,] = input.parse()?;
+                }
+                continue;
             }
 
             let ident: syn::Ident = input.parse()?;
             match &ident.to_string()[..] {
+                "path" => {
+                    if path.is_some() {
+                        return Err(syn::Error::new(ident.span(), "duplicate path option"));
+                    }
+
+                    let _: syn::Token![=] = input.parse()?;
+                    path = Some(input.parse()?);
+                }
+                "extends" => {
+                    if extends.is_some() {
+                        return Err(syn::Error::new(ident.span(), "duplicate extends block"));
+                    }
+
+                    let content;
+                    syn::parenthesized!(content in input);
+                    extends = Some(content.parse()?);
+                }
                 "constructions" => {
                     if constructions.is_some() {
                         return Err(syn::Error::new(
@@ -84,6 +282,27 @@ impl syn::parse::Parse for AttributeOptions {
                     attribute_substitutions =
                         Some(attribute_substitution_declaration::parse(input)?);
                 }
+                "types" => {
+                    if types.is_some() {
+                        return Err(syn::Error::new(ident.span(), "duplicate types block"));
+                    }
+
+                    types = Some(type_declaration::parse(input)?);
+                }
+                "consts" => {
+                    if consts.is_some() {
+                        return Err(syn::Error::new(ident.span(), "duplicate consts block"));
+                    }
+
+                    consts = Some(const_declaration::parse(input)?);
+                }
+                "idents" => {
+                    if idents.is_some() {
+                        return Err(syn::Error::new(ident.span(), "duplicate idents block"));
+                    }
+
+                    idents = Some(ident_declaration::parse(input)?);
+                }
                 _ => {
                     return Err(syn::Error::new(ident.span(), "unexpected"));
                 }
@@ -101,32 +320,67 @@ pub(crate) mod tests {
     use proc_macro2::TokenStream;
     use quote::ToTokens;
 
+    use crate::attributes::extend_parameter_list;
+
     use super::{
         attribute_substitution_declaration::tests::AttributeSubstitutionDeclarationForTest,
-        construction_declaration::tests::ConstructionDeclarationForTest, AttributeOptions,
+        const_declaration::tests::ConstDeclarationForTest,
+        construction_declaration::tests::ConstructionDeclarationForTest,
+        ident_declaration::tests::IdentDeclarationForTest,
+        type_declaration::tests::TypeDeclarationForTest, AttributeOptions,
     };
 
     #[derive(Debug, PartialEq, Eq, typed_builder::TypedBuilder)]
     pub struct AttributeOptionsForTest {
         mbe_header: String,
+        #[builder(default)]
+        crate_path: Option<String>,
+        #[builder(default)]
+        path: Option<String>,
+        #[builder(default)]
+        extends: Option<String>,
         constructions: Vec<ConstructionDeclarationForTest>,
         attribute_substitutions: Vec<AttributeSubstitutionDeclarationForTest>,
+        #[builder(default)]
+        types: Vec<TypeDeclarationForTest>,
+        #[builder(default)]
+        consts: Vec<ConstDeclarationForTest>,
+        #[builder(default)]
+        idents: Vec<IdentDeclarationForTest>,
     }
     impl From<AttributeOptions> for AttributeOptionsForTest {
         fn from(value: AttributeOptions) -> Self {
             let AttributeOptions {
                 mbe_header,
+                crate_path,
+                path,
+                extends,
                 constructions,
                 attribute_substitutions: attr_subst,
+                types,
+                consts,
+                idents,
             } = value;
 
             let mbe_header = mbe_header.into_token_stream().to_string();
+            let crate_path = crate_path.map(|x| x.into_token_stream().to_string());
+            let path = path.map(|x| x.value());
+            let extends = extends.map(|x| x.into_token_stream().to_string());
             let constructions = constructions.into_iter().map(|x| x.into()).collect();
             let attr_subst = attr_subst.into_iter().map(|x| x.into()).collect();
+            let types = types.into_iter().map(|x| x.into()).collect();
+            let consts = consts.into_iter().map(|x| x.into()).collect();
+            let idents = idents.into_iter().map(|x| x.into()).collect();
             Self {
                 mbe_header,
+                crate_path,
+                path,
+                extends,
                 constructions,
                 attribute_substitutions: attr_subst,
+                types,
+                consts,
+                idents,
             }
         }
     }
@@ -167,6 +421,136 @@ pub(crate) mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn types() {
+        let input = quote::quote!(
+            macro_rules! the_macro_name;
+            types(Foo: crate::Foo + Send, Bar),
+        );
+
+        let expected = AttributeOptionsForTest::builder()
+            .mbe_header(quote::quote!(macro_rules! the_macro_name).to_string())
+            .constructions(vec![])
+            .attribute_substitutions(vec![])
+            .types(vec![
+                TypeDeclarationForTest::builder()
+                    .target_name("Foo".to_string())
+                    .bounds(quote::quote!(crate::Foo + Send).to_string())
+                    .build(),
+                TypeDeclarationForTest::builder()
+                    .target_name("Bar".to_string())
+                    .bounds("".to_string())
+                    .build(),
+            ])
+            .build();
+
+        let actual: AttributeOptions = syn::parse2(input).unwrap();
+        let actual: AttributeOptionsForTest = actual.into();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn consts() {
+        let input = quote::quote!(
+            macro_rules! the_macro_name;
+            consts(FOO: usize, BAR: [u8; 4]),
+        );
+
+        let expected = AttributeOptionsForTest::builder()
+            .mbe_header(quote::quote!(macro_rules! the_macro_name).to_string())
+            .constructions(vec![])
+            .attribute_substitutions(vec![])
+            .consts(vec![
+                ConstDeclarationForTest::builder()
+                    .target_name("FOO".to_string())
+                    .ty("usize".to_string())
+                    .build(),
+                ConstDeclarationForTest::builder()
+                    .target_name("BAR".to_string())
+                    .ty(quote::quote!([u8; 4]).to_string())
+                    .build(),
+            ])
+            .build();
+
+        let actual: AttributeOptions = syn::parse2(input).unwrap();
+        let actual: AttributeOptionsForTest = actual.into();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn construction_defaults() {
+        let input = quote::quote!(
+            macro_rules! the_macro_name;
+            constructions(FOO -> i32 = 42, BAR -> crate::Bar),
+        );
+
+        let expected = AttributeOptionsForTest::builder()
+            .mbe_header(quote::quote!(macro_rules! the_macro_name).to_string())
+            .constructions(vec![
+                ConstructionDeclarationForTest::builder()
+                    .target_name("FOO".to_string())
+                    .ty("i32".to_string())
+                    .default(Some("42".to_string()))
+                    .build(),
+                ConstructionDeclarationForTest::builder()
+                    .target_name("BAR".to_string())
+                    .ty(quote::quote!(crate::Bar).to_string())
+                    .build(),
+            ])
+            .attribute_substitutions(vec![])
+            .build();
+
+        let actual: AttributeOptions = syn::parse2(input).unwrap();
+        let actual: AttributeOptionsForTest = actual.into();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn attribute_substitution_defaults() {
+        let input = quote::quote!(
+            macro_rules! the_macro_name;
+            attribute_substitutions(FOO = #[foo] #[bar], BAZ, QUX?, QUUX(.., n: i32)),
+        );
+
+        let expected = AttributeOptionsForTest::builder()
+            .mbe_header(quote::quote!(macro_rules! the_macro_name).to_string())
+            .constructions(vec![])
+            .attribute_substitutions(vec![
+                AttributeSubstitutionDeclarationForTest::builder()
+                    .target_name("FOO".to_string())
+                    .default(Some(vec![
+                        quote::quote!(#[foo]).to_string(),
+                        quote::quote!(#[bar]).to_string(),
+                    ]))
+                    .build(),
+                AttributeSubstitutionDeclarationForTest::builder()
+                    .target_name("BAZ".to_string())
+                    .build(),
+                AttributeSubstitutionDeclarationForTest::builder()
+                    .target_name("QUX".to_string())
+                    .default(Some(vec![]))
+                    .build(),
+                AttributeSubstitutionDeclarationForTest::builder()
+                    .target_name("QUUX".to_string())
+                    .parameter_list_extension(Some(
+                        extend_parameter_list::tests::AttributeOptionsForTest::builder()
+                            .direction(extend_parameter_list::Direction::Append)
+                            .parameter_list(quote::quote!(n: i32).to_string())
+                            .build(),
+                    ))
+                    .build(),
+            ])
+            .build();
+
+        let actual: AttributeOptions = syn::parse2(input).unwrap();
+        let actual: AttributeOptionsForTest = actual.into();
+
+        assert_eq!(actual, expected);
+    }
+
     fn do_test_only_mbe_header(header: TokenStream) {
         let input = quote::quote!(#header);
 
@@ -228,4 +612,125 @@ pub(crate) mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn extends() {
+        let input = quote::quote!(
+            macro_rules! the_macro_name;
+            extends(crate::the_base_macro_name),
+            constructions(FOO -> i32),
+        );
+
+        let expected = AttributeOptionsForTest::builder()
+            .mbe_header(quote::quote!(macro_rules! the_macro_name).to_string())
+            .extends(Some(quote::quote!(crate::the_base_macro_name).to_string()))
+            .constructions(vec![ConstructionDeclarationForTest::builder()
+                .target_name("FOO".to_string())
+                .ty("i32".to_string())
+                .build()])
+            .attribute_substitutions(vec![])
+            .build();
+
+        let actual: AttributeOptions = syn::parse2(input).unwrap();
+        let actual: AttributeOptionsForTest = actual.into();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn merge() {
+        let mut base: AttributeOptions = syn::parse2(quote::quote!(
+            macro_rules! the_base_macro_name;
+            constructions(FOO -> i32),
+        ))
+        .unwrap();
+        let derived: AttributeOptions = syn::parse2(quote::quote!(
+            macro_rules! the_macro_name;
+            extends(the_base_macro_name),
+            constructions(BAR -> i32),
+            attribute_substitutions(BAZ),
+        ))
+        .unwrap();
+
+        let expected = AttributeOptionsForTest::builder()
+            .mbe_header(quote::quote!(macro_rules! the_base_macro_name).to_string())
+            .constructions(vec![
+                ConstructionDeclarationForTest::builder()
+                    .target_name("FOO".to_string())
+                    .ty("i32".to_string())
+                    .build(),
+                ConstructionDeclarationForTest::builder()
+                    .target_name("BAR".to_string())
+                    .ty("i32".to_string())
+                    .build(),
+            ])
+            .attribute_substitutions(vec![AttributeSubstitutionDeclarationForTest::builder()
+                .target_name("BAZ".to_string())
+                .build()])
+            .build();
+
+        base.merge(derived).unwrap();
+        let actual: AttributeOptionsForTest = base.into();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn merge_duplicates() {
+        let mut base: AttributeOptions = syn::parse2(quote::quote!(
+            macro_rules! the_base_macro_name;
+            constructions(FOO -> i32),
+        ))
+        .unwrap();
+        let derived: AttributeOptions = syn::parse2(quote::quote!(
+            macro_rules! the_macro_name;
+            extends(the_base_macro_name),
+            constructions(FOO -> i64),
+        ))
+        .unwrap();
+
+        assert!(base.merge(derived).is_err());
+    }
+
+    #[test]
+    fn crate_path() {
+        let input = quote::quote!(
+            macro_rules! the_macro_name;
+            crate = ::a_facade::mod_template,
+            constructions(),
+        );
+
+        let expected = AttributeOptionsForTest::builder()
+            .mbe_header(quote::quote!(macro_rules! the_macro_name).to_string())
+            .crate_path(Some(quote::quote!(::a_facade::mod_template).to_string()))
+            .constructions(vec![])
+            .attribute_substitutions(vec![])
+            .build();
+
+        let actual: AttributeOptions = syn::parse2(input).unwrap();
+        let actual: AttributeOptionsForTest = actual.into();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn path() {
+        let input = quote::quote!(
+            macro_rules! the_macro_name;
+            path = "templates/foo.rs",
+            constructions(),
+        );
+
+        let expected = AttributeOptionsForTest::builder()
+            .mbe_header(quote::quote!(macro_rules! the_macro_name).to_string())
+            .path(Some("templates/foo.rs".to_string()))
+            .constructions(vec![])
+            .attribute_substitutions(vec![])
+            .build();
+
+        let actual: AttributeOptions = syn::parse2(input).unwrap();
+        let actual: AttributeOptionsForTest = actual.into();
+
+        assert_eq!(actual, expected);
+    }
 }
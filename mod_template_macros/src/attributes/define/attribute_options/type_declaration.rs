@@ -0,0 +1,99 @@
+use std::collections::HashSet;
+
+use syn::punctuated::Punctuated;
+
+pub struct TypeDeclaration {
+    target_name_ident: syn::Ident,
+    bounds: Punctuated<syn::TypeParamBound, syn::Token![+]>,
+}
+
+impl TypeDeclaration {
+    pub fn target_name_ident(&self) -> &syn::Ident {
+        &self.target_name_ident
+    }
+    pub fn bounds(&self) -> &Punctuated<syn::TypeParamBound, syn::Token![+]> {
+        &self.bounds
+    }
+}
+
+pub fn parse(input: syn::parse::ParseStream) -> syn::Result<Vec<TypeDeclaration>> {
+    fn parse_param(input: syn::parse::ParseStream) -> syn::Result<TypeDeclaration> {
+        let target_name_ident: syn::Ident = input.parse()?;
+        let colon: Option<syn::Token![:]> = input.parse()?;
+        let bounds = if colon.is_some() {
+            let mut bounds = Punctuated::new();
+            loop {
+                if input.is_empty() || input.peek(syn::Token![,]) {
+                    break;
+                }
+                bounds.push_value(input.parse()?);
+                if !input.peek(syn::Token![+]) {
+                    break;
+                }
+                bounds.push_punct(input.parse()?);
+            }
+            bounds
+        } else {
+            Punctuated::new()
+        };
+
+        Ok(TypeDeclaration {
+            target_name_ident,
+            bounds,
+        })
+    }
+
+    let vec: Vec<_> = {
+        let content;
+        syn::parenthesized!(content in input);
+        content
+            .parse_terminated(parse_param, syn::Token![,])?
+            .into_iter()
+            .collect()
+    };
+
+    {
+        let mut previous_names = HashSet::new();
+        for TypeDeclaration {
+            target_name_ident, ..
+        } in &vec
+        {
+            let target_name = target_name_ident.to_string();
+            if previous_names.contains(&target_name) {
+                return Err(syn::Error::new(
+                    target_name_ident.span(),
+                    "duplicate target name",
+                ));
+            }
+            previous_names.insert(target_name);
+        }
+    }
+
+    Ok(vec)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use quote::ToTokens;
+
+    use super::TypeDeclaration;
+
+    #[derive(Debug, PartialEq, Eq, typed_builder::TypedBuilder)]
+    pub struct TypeDeclarationForTest {
+        target_name: String,
+        bounds: String,
+    }
+    impl From<TypeDeclaration> for TypeDeclarationForTest {
+        fn from(value: TypeDeclaration) -> Self {
+            let TypeDeclaration {
+                target_name_ident,
+                bounds,
+            } = value;
+
+            Self {
+                target_name: target_name_ident.to_string(),
+                bounds: bounds.into_token_stream().to_string(),
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::collections::HashSet;
+
+use proc_macro2::{Ident, TokenStream, TokenTree};
+use quote::ToTokens;
+
+use super::AttributeOptions;
+
+/// Puts a function returning the default expression of each construction
+/// declared with one at the beginning of the template, so that the expression
+/// is type-checked once, instead of where the construction is used.
+///
+/// The parameters declared in the parameter list extensions of the attribute
+/// substitutions are the parameters of those functions, since the default
+/// expressions can rely on them, e.g. `crate::Multiplier(factor)`.
+pub fn add_construction_default_checks(
+    input_item: TokenStream,
+    opts: &AttributeOptions,
+) -> Result<TokenStream, syn::Error> {
+    let decls: Vec<_> = (opts.constructions().iter())
+        .filter(|decl| decl.default().is_some())
+        .collect();
+    if decls.is_empty() {
+        return Ok(input_item);
+    }
+
+    let mut params: Vec<syn::FnArg> = vec![];
+    let mut param_names = HashSet::new();
+    for decl in opts.attribute_substitutions() {
+        let Some(ext) = decl.parameter_list_extension() else {
+            continue;
+        };
+        let list = TokenStream::from_iter(ext.parameter_list().clone());
+        let parser = syn::punctuated::Punctuated::<syn::FnArg, syn::Token![,]>::parse_terminated;
+        let Ok(args) = syn::parse::Parser::parse2(parser, list) else {
+            continue;
+        };
+        // NOTE: the first one wins if the same parameter is declared more
+        // than once.
+        for arg in args {
+            let name = match &arg {
+                syn::FnArg::Typed(pat_type) => pat_type.pat.to_token_stream().to_string(),
+                syn::FnArg::Receiver(..) => continue,
+            };
+            if param_names.insert(name) {
+                params.push(arg);
+            }
+        }
+    }
+
+    let mut item: syn::ItemMod = syn::parse2(input_item)?;
+    let Some((_, items)) = &mut item.content else {
+        return Ok(item.into_token_stream());
+    };
+    let checks = decls.into_iter().map(|decl| -> syn::Item {
+        let target_name_ident = decl.target_name_ident();
+        let fn_ident = Ident::new(
+            &format!("__mod_template__default__{}", target_name_ident),
+            target_name_ident.span(),
+        );
+        let ty = decl.ty();
+        let default = decl.default().expect("filtered above");
+        let asyncness = contains_await(default.to_token_stream()).then(|| quote::quote!(async));
+        syn::parse_quote!(
+            #[allow(dead_code, non_snake_case, unused_variables)]
+            #asyncness fn #fn_ident(#(#params),*) -> #ty {
+                #default
+            }
+        )
+    });
+    items.splice(0..0, checks);
+
+    Ok(item.into_token_stream())
+}
+
+fn contains_await(tokens: TokenStream) -> bool {
+    let mut is_after_dot = false;
+    for tt in tokens {
+        match tt {
+            TokenTree::Ident(ident) if is_after_dot && ident == "await" => return true,
+            TokenTree::Group(group) if contains_await(group.stream()) => return true,
+            TokenTree::Punct(ref punct) => {
+                is_after_dot = punct.as_char() == '.';
+                continue;
+            }
+            _ => {}
+        }
+        is_after_dot = false;
+    }
+    false
+}
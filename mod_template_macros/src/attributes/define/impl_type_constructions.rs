@@ -0,0 +1,345 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::ToTokens;
+use syn::visit_mut::{self, VisitMut};
+
+/// The attribute that the substituter of `__CONSTRUCT` puts on functions in the
+/// compiler check dummy module for constructions of `impl` types, with
+/// `«pattern»: «type»` as its arguments.
+pub const MARKER: &str = "__mod_template__impl_type_construction";
+
+/// Makes the compiler check dummy module type-check the bodies of functions
+/// using constructions of `impl` types, without changing their signatures.
+///
+/// There is no concrete type to construct such values with, so the body of
+/// each of those functions is checked in a copy of it named
+/// `__mod_template__checked__«name»`, which takes the values as arguments
+/// instead, i.e. as anonymous generic types bounded by the traits. The body of
+/// the function itself is replaced with `unreachable!()`, so calls to it from
+/// elsewhere in the template are still checked against its actual signature.
+pub fn check_impl_type_constructions(
+    input_item: TokenStream,
+    crate_path: &syn::Path,
+) -> Result<TokenStream, syn::Error> {
+    let mut item: syn::ItemMod = syn::parse2(input_item)?;
+    let mut checker = Checker {
+        crate_path,
+        err: None,
+    };
+    checker.visit_item_mod_mut(&mut item);
+
+    match checker.err {
+        Some(err) => Err(err),
+        None => Ok(item.into_token_stream()),
+    }
+}
+
+struct Checker<'a> {
+    crate_path: &'a syn::Path,
+    err: Option<syn::Error>,
+}
+
+/// The parts of the checked copy of a function.
+struct CheckedCopy {
+    attrs: Vec<syn::Attribute>,
+    sig: syn::Signature,
+    block: syn::Block,
+}
+
+impl Checker<'_> {
+    fn push_err(&mut self, new_err: syn::Error) {
+        match self.err {
+            Some(ref mut err) => err.combine(new_err),
+            None => self.err = Some(new_err),
+        }
+    }
+
+    fn is_crate_attr(&self, attr: &syn::Attribute, name: &str) -> bool {
+        let crate_path = self.crate_path;
+        let name_ident = Ident::new(name, Span::call_site());
+        let path: syn::Path = syn::parse_quote!(#crate_path::#name_ident);
+        attr.path().to_token_stream().to_string() == path.to_token_stream().to_string()
+    }
+
+    /// Replaces the body of the function with `unreachable!()`, and returns
+    /// the parts of its checked copy, if it uses any constructions of `impl`
+    /// types.
+    fn split(
+        &mut self,
+        attrs: &mut Vec<syn::Attribute>,
+        sig: &syn::Signature,
+        block: &mut syn::Block,
+        what: Option<&str>,
+    ) -> Option<CheckedCopy> {
+        let markers: Vec<&syn::Attribute> = attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident(MARKER))
+            .collect();
+        let first_marker = markers.first()?;
+
+        let returns_impl_type = match &sig.output {
+            syn::ReturnType::Default => false,
+            syn::ReturnType::Type(_, ty) => ty
+                .to_token_stream()
+                .into_iter()
+                .any(|tt| matches!(tt, proc_macro2::TokenTree::Ident(ident) if ident == "impl")),
+        };
+        let reason = match what {
+            Some(what) => Some(format!("can't be used in {}", what)),
+            None if returns_impl_type => {
+                Some("can't be used in functions returning `impl` types".to_string())
+            }
+            None => None,
+        };
+        if let Some(reason) = reason {
+            let span = (first_marker.meta.require_list().ok())
+                .and_then(|list| list.tokens.clone().into_iter().next())
+                .map_or(sig.ident.span(), |tt| tt.span());
+            self.push_err(syn::Error::new(
+                span,
+                format!("constructions of `impl` types {}", reason),
+            ));
+            attrs.retain(|attr| !attr.path().is_ident(MARKER));
+            return None;
+        }
+
+        let crate_path = self.crate_path;
+        let mut checked_attrs: Vec<syn::Attribute> = vec![];
+        for attr in attrs.iter() {
+            if attr.path().is_ident(MARKER) {
+                let Ok(list) = attr.meta.require_list() else {
+                    continue;
+                };
+                let construction = &list.tokens;
+                checked_attrs.push(syn::parse_quote!(
+                    #[#crate_path::extend_parameter_list(.., #construction)]
+                ));
+            } else if self.is_crate_attr(attr, "construct")
+                || self.is_crate_attr(attr, "extend_parameter_list")
+                || ["cfg", "allow", "expect", "warn", "deny", "forbid"]
+                    .iter()
+                    .any(|name| attr.path().is_ident(name))
+            {
+                checked_attrs.push(attr.clone());
+            }
+        }
+        checked_attrs.push(syn::parse_quote!(#[allow(dead_code)]));
+
+        // NOTE: test attributes are removed as well, otherwise the function
+        // would be run as a test and panic.
+        attrs.retain(|attr| {
+            !attr.path().is_ident(MARKER)
+                && !self.is_crate_attr(attr, "construct")
+                && attr
+                    .path()
+                    .segments
+                    .last()
+                    .is_none_or(|segment| segment.ident != "test")
+        });
+        attrs.push(syn::parse_quote!(
+            #[allow(dead_code, unused_variables, unused_mut)]
+        ));
+
+        let mut checked_sig = sig.clone();
+        checked_sig.ident = Ident::new(
+            &format!("__mod_template__checked__{}", sig.ident),
+            sig.ident.span(),
+        );
+        let checked_block = std::mem::replace(block, syn::parse_quote!({ unreachable!() }));
+
+        Some(CheckedCopy {
+            attrs: checked_attrs,
+            sig: checked_sig,
+            block: checked_block,
+        })
+    }
+
+    fn split_item_fns(&mut self, items: &mut Vec<syn::Item>) {
+        let mut index = 0;
+        while index < items.len() {
+            if let syn::Item::Fn(item_fn) = &mut items[index] {
+                if let Some(copy) =
+                    self.split(&mut item_fn.attrs, &item_fn.sig, &mut item_fn.block, None)
+                {
+                    index += 1;
+                    items.insert(
+                        index,
+                        syn::Item::Fn(syn::ItemFn {
+                            attrs: copy.attrs,
+                            vis: syn::Visibility::Inherited,
+                            sig: copy.sig,
+                            block: Box::new(copy.block),
+                        }),
+                    );
+                }
+            }
+            index += 1;
+        }
+    }
+}
+
+impl VisitMut for Checker<'_> {
+    fn visit_item_mod_mut(&mut self, i: &mut syn::ItemMod) {
+        if let Some((_, items)) = &mut i.content {
+            self.split_item_fns(items);
+        }
+        visit_mut::visit_item_mod_mut(self, i);
+    }
+
+    fn visit_block_mut(&mut self, i: &mut syn::Block) {
+        let mut items: Vec<syn::Item> = vec![];
+        let mut stmts: Vec<syn::Stmt> = vec![];
+        // NOTE: items in blocks are statements, so they're taken out and put
+        // back with their copies.
+        for stmt in std::mem::take(&mut i.stmts) {
+            match stmt {
+                syn::Stmt::Item(item @ syn::Item::Fn(_)) => {
+                    items.push(item);
+                    self.split_item_fns(&mut items);
+                    stmts.extend(items.drain(..).map(syn::Stmt::Item));
+                }
+                stmt => stmts.push(stmt),
+            }
+        }
+        i.stmts = stmts;
+        visit_mut::visit_block_mut(self, i);
+    }
+
+    fn visit_item_impl_mut(&mut self, i: &mut syn::ItemImpl) {
+        let what = i
+            .trait_
+            .is_some()
+            .then_some("methods of trait implementations");
+        let mut index = 0;
+        while index < i.items.len() {
+            if let syn::ImplItem::Fn(impl_item_fn) = &mut i.items[index] {
+                if let Some(copy) = self.split(
+                    &mut impl_item_fn.attrs,
+                    &impl_item_fn.sig,
+                    &mut impl_item_fn.block,
+                    what,
+                ) {
+                    index += 1;
+                    i.items.insert(
+                        index,
+                        syn::ImplItem::Fn(syn::ImplItemFn {
+                            attrs: copy.attrs,
+                            vis: syn::Visibility::Inherited,
+                            defaultness: None,
+                            sig: copy.sig,
+                            block: copy.block,
+                        }),
+                    );
+                }
+            }
+            index += 1;
+        }
+        visit_mut::visit_item_impl_mut(self, i);
+    }
+
+    fn visit_trait_item_fn_mut(&mut self, i: &mut syn::TraitItemFn) {
+        if let Some(block) = &mut i.default {
+            self.split(&mut i.attrs, &i.sig, block, Some("methods of traits"));
+        }
+        visit_mut::visit_trait_item_fn_mut(self, i);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_impl_type_constructions;
+
+    #[test]
+    fn basic() {
+        let input_item = quote::quote! {
+            mod __ {
+                #[__mod_template__impl_type_construction(mut foo: impl Foo)]
+                #[::mod_template::construct(bar: Bar = (|| -> Bar { unreachable!() })())]
+                #[::mod_template::extend_parameter_list(.., baz: i32)]
+                #[test]
+                #[inline]
+                fn an_fn(n: i32) {
+                    foo.foo(bar, baz, n);
+                }
+
+                fn a_second_fn() {
+                    fn a_nested_fn() {}
+                }
+
+                impl AStruct {
+                    #[__mod_template__impl_type_construction(foo: impl Foo)]
+                    fn a_method(&self) {
+                        foo.foo();
+                    }
+                }
+            }
+        };
+
+        let expected = quote::quote! {
+            mod __ {
+                #[::mod_template::extend_parameter_list(.., baz: i32)]
+                #[inline]
+                #[allow(dead_code, unused_variables, unused_mut)]
+                fn an_fn(n: i32) {
+                    unreachable!()
+                }
+                #[::mod_template::extend_parameter_list(.., mut foo: impl Foo)]
+                #[::mod_template::construct(bar: Bar = (|| -> Bar { unreachable!() })())]
+                #[::mod_template::extend_parameter_list(.., baz: i32)]
+                #[allow(dead_code)]
+                fn __mod_template__checked__an_fn(n: i32) {
+                    foo.foo(bar, baz, n);
+                }
+
+                fn a_second_fn() {
+                    fn a_nested_fn() {}
+                }
+
+                impl AStruct {
+                    #[allow(dead_code, unused_variables, unused_mut)]
+                    fn a_method(&self) {
+                        unreachable!()
+                    }
+                    #[::mod_template::extend_parameter_list(.., foo: impl Foo)]
+                    #[allow(dead_code)]
+                    fn __mod_template__checked__a_method(&self) {
+                        foo.foo();
+                    }
+                }
+            }
+        };
+
+        let crate_path = syn::parse_quote!(::mod_template);
+        let actual = check_impl_type_constructions(input_item, &crate_path).unwrap();
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn unsupported_functions() {
+        let input_item = quote::quote! {
+            mod __ {
+                #[__mod_template__impl_type_construction(foo: impl Foo)]
+                fn an_fn() -> impl Foo {
+                    foo
+                }
+
+                impl ATrait for AStruct {
+                    #[__mod_template__impl_type_construction(foo: impl Foo)]
+                    fn a_trait_method(&self) {}
+                }
+            }
+        };
+
+        let crate_path = syn::parse_quote!(::mod_template);
+        let err = check_impl_type_constructions(input_item, &crate_path).unwrap_err();
+
+        let messages: Vec<String> = err.into_iter().map(|err| err.to_string()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "constructions of `impl` types can't be used in functions returning `impl` types",
+                "constructions of `impl` types can't be used in methods of trait implementations",
+            ]
+        );
+    }
+}
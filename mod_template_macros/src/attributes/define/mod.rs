@@ -1,42 +1,179 @@
 pub(crate) mod attribute_options;
+mod construction_defaults;
+mod impl_type_constructions;
+mod out_of_line_mods;
+mod placeholder_consts;
+mod type_parameters;
 
 use std::{
     collections::{HashMap, HashSet},
+    path::PathBuf,
     rc::Rc,
 };
 
-use proc_macro2::{Ident, TokenStream, TokenTree};
+use proc_macro2::{Group, Ident, TokenStream, TokenTree};
 
-pub use attribute_options::{
-    AttributeOptions, AttributeSubstitutionDeclaration, ConstructionDeclaration,
+pub use attribute_options::{AttributeOptions, TypeDeclaration};
+use quote::{ToTokens, TokenStreamExt};
+use syn::{
+    spanned::Spanned,
+    visit_mut::{self, VisitMut},
 };
-use quote::ToTokens;
 
 use crate::{
+    attributes::__monomorphize_mod::{extends, kind_with_article},
     helper_attributes::{
         __CONSTRUCT::AttributeOptions as ConstructHelperAttributeOptions,
         __SUBSTITUTE::AttributeOptions as SubstituteHelperAttributeOptions,
     },
-    utils::substitute_attributes::{substitute_attributes, Substituter},
+    utils::{
+        inject_into_mods::inject_into_nested_mods,
+        nested_instances::{forward_into_nested_instances, ForwardedKind},
+        substitute_attributes::{substitute_attributes, Substituter},
+    },
+};
+
+use self::{
+    construction_defaults::add_construction_default_checks,
+    impl_type_constructions::{
+        check_impl_type_constructions, MARKER as IMPL_TYPE_CONSTRUCTION_MARKER,
+    },
+    out_of_line_mods::{load_out_of_line_mods, load_template_file, template_dirs, ModDirs},
+    placeholder_consts::{bind_placeholder_consts, make_placeholder_consts},
+    type_parameters::{make_type_parameters_generic, mentions},
 };
 
 pub fn define(attr: TokenStream, item: TokenStream) -> TokenStream {
-    check_top_mod_on_error_abort(item.clone());
+    check_top_item_on_error_abort(item.clone());
     let opts: AttributeOptions = match syn::parse2(attr.clone()) {
         Ok(attr) => attr,
         Err(err) => return err.to_compile_error(),
     };
+
+    let kind = TemplateKind::of(&item);
+    let result = match kind {
+        TemplateKind::Mod => load_files(item, &opts),
+        _ => check_non_mod_template(item.clone(), kind, &opts).map(|()| (item, vec![])),
+    };
+    let (item, loaded_paths) = match result {
+        Ok(output) => output,
+        Err(err) => return err.to_compile_error(),
+    };
+    // NOTE: so that the template is recompiled when the loaded files change.
+    let file_tracking = loaded_paths.iter().map(|path| {
+        let path = path.to_string_lossy();
+        quote::quote!(
+            const _: &[u8] = include_bytes!(#path);
+        )
+    });
     let mbe_header = opts.mbe_header();
     let macro_name_ident = mbe_header.name_ident();
 
-    let output_item = item.clone();
+    // NOTE: the template is pasted into the body of the defined macro, where
+    // `$` would be interpreted by the macro itself, e.g. those in the
+    // `macro_rules!` in the template. So they are escaped as `$dollar`, where
+    // `dollar` is bound to `$` by letting the macro call itself. It's done
+    // only if necessary, since calling itself requires the macro to be in
+    // scope by its name, unless it's exported, in which case it calls itself
+    // by its path in the crate defining it.
+    let has_dollars = contains_dollars(&attr) || contains_dollars(&item);
+    let (output_attr, output_item) = if has_dollars {
+        (escape_dollars(attr.clone()), escape_dollars(item.clone()))
+    } else {
+        (attr.clone(), item.clone())
+    };
+    // NOTE: `$crate` is a `$` that shouldn't be escaped, so the replacement
+    // comes after the escaping.
+    let output_attr = replace_crate_placeholders(output_attr, &quote::quote!($crate));
+    let output_item = replace_crate_placeholders(output_item, &quote::quote!($crate));
+    let crate_path =
+        replace_crate_placeholders(opts.crate_path().to_token_stream(), &quote::quote!($crate));
+    let dollar_pattern = has_dollars.then(|| quote::quote!(@__mod_template__dollar ($dollar:tt)));
+    let dollar_entry_arm = has_dollars.then(|| {
+        let macro_path = if mbe_header.is_exported() {
+            quote::quote!($crate::#macro_name_ident)
+        } else {
+            macro_name_ident.to_token_stream()
+        };
+        quote::quote!(
+            ($($input:tt)*) => {
+                #macro_path! { @__mod_template__dollar ($) $($input)* }
+            };
+        )
+    });
 
-    let dummy_wrapper_ident = Ident::new(
-        &format!("__mod_template__compiler_check_dummy__{}", macro_name_ident),
-        macro_name_ident.span(),
-    );
+    if let Some(base_macro_path) = opts.extends() {
+        // NOTE: the declarations of the base template are unknown here, so the
+        // compiler check dummy of this template is made by the macro of the
+        // base template, along with the items of both. And for the same
+        // reason, everything is passed to the macro of the base template when
+        // this template is instantiated, which monomorphizes the items of both
+        // at once.
+        let dummy_base_macro_path =
+            replace_crate_placeholders(base_macro_path.to_token_stream(), &quote::quote!(crate));
+        let dummy_attr = replace_crate_placeholders(attr, &quote::quote!(crate));
+        let dummy_item = replace_crate_placeholders(item, &quote::quote!(crate));
+        let base_macro_path =
+            replace_crate_placeholders(base_macro_path.to_token_stream(), &quote::quote!($crate));
+        return quote::quote! {
+            #(#file_tracking)*
+
+            #dummy_base_macro_path! {
+                @__mod_template__compiler_check_dummy { ((#dummy_attr) #dummy_item) }
+            }
+
+            #mbe_header {
+                (#dollar_pattern @__mod_template__compiler_check_dummy { $($derived:tt)* }) => {
+                    #base_macro_path! {
+                        @__mod_template__compiler_check_dummy {
+                            ((#output_attr) #output_item) $($derived)*
+                        }
+                    }
+                };
+                (#dollar_pattern @__mod_template__extends { $($derived:tt)* } $($input:tt)*) => {
+                    #base_macro_path! {
+                        @__mod_template__extends { ((#output_attr) #output_item) $($derived)* }
+                        $($input)*
+                    }
+                };
+                (#dollar_pattern $($input:tt)*) => {
+                    #base_macro_path! {
+                        @__mod_template__extends { ((#output_attr) #output_item) }
+                        $($input)*
+                    }
+                };
+                #dollar_entry_arm
+            }
+        };
+    }
+
+    let separated_dollar_entry_arm = dollar_entry_arm.map(|arm| quote::quote!(; #arm));
+    let dummy_wrapper_ident = make_dummy_wrapper_ident(macro_name_ident);
+    // NOTE: the compiler check dummy is always in the crate defining the
+    // template.
+    let dummy_opts: AttributeOptions =
+        match syn::parse2(replace_crate_placeholders(attr, &quote::quote!(crate))) {
+            Ok(attr) => attr,
+            Err(err) => return err.to_compile_error(),
+        };
+    let dummy_item = replace_crate_placeholders(item, &quote::quote!(crate));
+    // NOTE: so that a template of a function or an `impl` block is checked
+    // like a module containing only that item, where the self type of the
+    // latter is a placeholder struct.
+    let dummy_item = match kind {
+        TemplateKind::Mod => dummy_item,
+        TemplateKind::Fn => quote::quote!(mod __ { #dummy_item }),
+        TemplateKind::Impl => {
+            let dummy_item = make_placeholder_trait_impl(dummy_item);
+            quote::quote!(mod __ {
+                #[allow(dead_code)]
+                struct __<__Self: ?Sized = ()>(::core::marker::PhantomData<__Self>);
+                #dummy_item
+            })
+        }
+    };
     let compiler_check_dummy_item =
-        match make_compiler_check_dummy_item_and_check_helper_attributes(item, &opts) {
+        match make_compiler_check_dummy_item_and_check_helper_attributes(dummy_item, &dummy_opts) {
             Ok(output) => output,
             Err(err) => {
                 let mut output = TokenStream::new();
@@ -46,13 +183,29 @@ pub fn define(attr: TokenStream, item: TokenStream) -> TokenStream {
         };
 
     quote::quote! {
+        #(#file_tracking)*
+
         #[cfg(test)]
         #[allow(non_snake_case)]
         mod #dummy_wrapper_ident { #compiler_check_dummy_item }
 
         #mbe_header {
-            ($($input:tt)*) => {
-                #[::mod_template::__monomorphize_mod(
+            // NOTE: for templates extending this one, ordered from the one
+            // directly extending this one to the most derived one.
+            (#dollar_pattern @__mod_template__compiler_check_dummy { $($derived:tt)* }) => {
+                #[#crate_path::__compiler_check_dummy((#output_attr) $($derived)*)]
+                #output_item
+            };
+            (#dollar_pattern @__mod_template__extends { $($derived:tt)* } $($input:tt)*) => {
+                #[#crate_path::__monomorphize_mod(
+                    (#output_attr),
+                    { $($input)* }
+                    $($derived)*
+                )]
+                #output_item
+            };
+            (#dollar_pattern $($input:tt)*) => {
+                #[#crate_path::__monomorphize_mod(
                     // TODO: just pass tokens after `;` in `attr`, since the
                     // macro name is unnecessary for the attribute
                     // `__monomorphize_mod`.
@@ -61,21 +214,200 @@ pub fn define(attr: TokenStream, item: TokenStream) -> TokenStream {
                     // and manually put those declarations required by
                     // `__monomorphize_mod` here, with commas to separate them.
                     // (`(#constructions, #attribute_substitutions),`)
-                    (#attr),
+                    (#output_attr),
                     { $($input)* }
                 )]
                 #output_item
             }
+            #separated_dollar_entry_arm
+        }
+    }
+}
+
+/// Makes the compiler check dummy of a template extending others, with the
+/// declarations and the items of all of them. The options are the ones of the
+/// base template, followed by the templates extending it, ordered from the one
+/// directly extending it to the most derived one, like those passed to the
+/// attribute `__monomorphize_mod`.
+pub fn __compiler_check_dummy(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut iter = attr.into_iter();
+    let mut opts: AttributeOptions = match iter.next() {
+        Some(TokenTree::Group(group)) => match syn::parse2(group.stream()) {
+            Ok(opts) => opts,
+            Err(err) => return err.to_compile_error(),
+        },
+        _ => proc_macro_error::abort_call_site!("expected the options of the base template"),
+    };
+    let derived_templates = match extends::parse_derived_templates(iter.collect()) {
+        Ok(derived_templates) => derived_templates,
+        Err(err) => return err.to_compile_error(),
+    };
+    let Some(most_derived) = derived_templates.last() else {
+        proc_macro_error::abort_call_site!("expected templates extending the base template");
+    };
+    let macro_name_ident = most_derived
+        .define_attr_opts
+        .mbe_header()
+        .name_ident()
+        .clone();
+    let kind = TemplateKind::of(&item);
+    if kind != TemplateKind::Mod {
+        return syn::Error::new(
+            macro_name_ident.span(),
+            format!(
+                "a template of {} can't be extended",
+                kind_with_article(kind)
+            ),
+        )
+        .to_compile_error();
+    }
+
+    let Some(TokenTree::Group(mod_group)) = item.into_iter().last() else {
+        proc_macro_error::abort_call_site!("expected the base template");
+    };
+    let mut mod_items = mod_group.stream();
+    for derived_template in derived_templates {
+        if let Err(err) = opts.merge(derived_template.define_attr_opts) {
+            return err.to_compile_error();
+        }
+        mod_items.extend(derived_template.mod_items);
+    }
+
+    let dummy_wrapper_ident = make_dummy_wrapper_ident(&macro_name_ident);
+    let dummy_item = quote::quote!(mod __ { #mod_items });
+    let compiler_check_dummy_item =
+        match make_compiler_check_dummy_item_and_check_helper_attributes(dummy_item, &opts) {
+            Ok(output) => output,
+            Err(err) => return err.to_compile_error(),
+        };
+
+    // NOTE: unused items of the base templates are not reported here, since
+    // the compiler check dummies of the base templates report them already.
+    quote::quote! {
+        #[cfg(test)]
+        #[allow(non_snake_case, dead_code)]
+        mod #dummy_wrapper_ident { #compiler_check_dummy_item }
+    }
+}
+
+fn make_dummy_wrapper_ident(macro_name_ident: &Ident) -> Ident {
+    Ident::new(
+        &format!("__mod_template__compiler_check_dummy__{}", macro_name_ident),
+        macro_name_ident.span(),
+    )
+}
+
+/// Replaces every `__CRATE` with `replacement`, e.g. `$crate`.
+fn replace_crate_placeholders(input: TokenStream, replacement: &TokenStream) -> TokenStream {
+    let mut output = TokenStream::new();
+    for tt in input {
+        match tt {
+            TokenTree::Ident(ident) if ident == "__CRATE" => {
+                output.extend(replacement.clone().into_iter().map(|mut tt| {
+                    tt.set_span(ident.span());
+                    tt
+                }));
+            }
+            TokenTree::Group(group) => {
+                let mut new_group = Group::new(
+                    group.delimiter(),
+                    replace_crate_placeholders(group.stream(), replacement),
+                );
+                new_group.set_span(group.span());
+                output.append(new_group);
+            }
+            tt => output.append(tt),
+        }
+    }
+    output
+}
+
+fn contains_dollars(input: &TokenStream) -> bool {
+    input.clone().into_iter().any(|tt| match tt {
+        TokenTree::Punct(punct) => punct.as_char() == '$',
+        TokenTree::Group(group) => contains_dollars(&group.stream()),
+        _ => false,
+    })
+}
+
+/// Replaces every `$` with `$dollar`.
+fn escape_dollars(input: TokenStream) -> TokenStream {
+    let mut output = TokenStream::new();
+    for tt in input {
+        match tt {
+            TokenTree::Punct(punct) if punct.as_char() == '$' => {
+                output.append(punct.clone());
+                output.append(Ident::new("dollar", punct.span()));
+            }
+            TokenTree::Group(group) => {
+                let mut new_group = Group::new(group.delimiter(), escape_dollars(group.stream()));
+                new_group.set_span(group.span());
+                output.append(new_group);
+            }
+            tt => output.append(tt),
+        }
+    }
+    output
+}
+
+/// The kind of the item that the attribute `mod_template::define` is applied
+/// to, which is also the kind of the items defined by the defined macro.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TemplateKind {
+    /// `mod __ { … }`
+    Mod,
+    /// `fn __(…) { … }`
+    Fn,
+    /// `impl __ { … }` or `impl Trait for __ { … }`
+    Impl,
+}
+
+impl TemplateKind {
+    /// Tells the kind by the first keyword among `mod`, `fn` and `impl` at the
+    /// top level of the template.
+    pub fn of(input_item: &TokenStream) -> Self {
+        for tt in input_item.clone() {
+            let TokenTree::Ident(ident) = tt else {
+                continue;
+            };
+            match &ident.to_string()[..] {
+                "mod" => return Self::Mod,
+                "fn" => return Self::Fn,
+                "impl" => return Self::Impl,
+                _ => {}
+            }
+        }
+        Self::Mod
+    }
+
+    pub fn keyword(self) -> &'static str {
+        match self {
+            Self::Mod => "mod",
+            Self::Fn => "fn",
+            Self::Impl => "impl",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Mod => "module",
+            Self::Fn => "function",
+            Self::Impl => "`impl` block",
         }
     }
 }
 
-fn check_top_mod_on_error_abort(input_item: TokenStream) {
+fn check_top_item_on_error_abort(input_item: TokenStream) {
+    let kind = TemplateKind::of(&input_item);
+    let keyword = kind.keyword();
     let mut has_found_mod = false;
     let mut mod_name_ident: Option<Ident> = None;
-    for tt in input_item {
+    for tt in input_item.clone() {
         if let TokenTree::Punct(punct) = &tt {
-            if punct.as_char() == '#' {
+            // NOTE: the attributes of a template of a function are where its
+            // helper attributes go, and they are put after the ones in the
+            // header of the instance.
+            if punct.as_char() == '#' && kind != TemplateKind::Fn {
                 // NOTE: that's because one can also put attributes in the mod
                 // header and the order of attributes from those two places
                 // would be ambiguous.
@@ -86,7 +418,7 @@ fn check_top_mod_on_error_abort(input_item: TokenStream) {
         }
         if !has_found_mod {
             if let TokenTree::Ident(ident) = &tt {
-                if ident == "mod" {
+                if ident == keyword {
                     has_found_mod = true
                 }
             }
@@ -99,7 +431,28 @@ fn check_top_mod_on_error_abort(input_item: TokenStream) {
     }
 
     if !has_found_mod {
-        proc_macro_error::abort_call_site!("this attribute can only be applied to a `mod`");
+        proc_macro_error::abort_call_site!(
+            "this attribute can only be applied to a `mod`, a `fn` or an `impl`"
+        );
+    }
+    if kind == TemplateKind::Impl {
+        // NOTE: the self type isn't right after `impl` if there are generics or
+        // a trait, so it's checked by syn.
+        let item_impl: syn::ItemImpl = match syn::parse2(input_item) {
+            Ok(item_impl) => item_impl,
+            Err(err) => proc_macro_error::abort!(err.span(), "{}", err),
+        };
+        let is_placeholder = matches!(
+            &*item_impl.self_ty,
+            syn::Type::Path(type_path) if type_path.qself.is_none() && type_path.path.is_ident("__")
+        );
+        if !is_placeholder {
+            proc_macro_error::abort!(
+                item_impl.self_ty.span(),
+                "the self type should always be `__`, since it is specified by the macro call"
+            );
+        }
+        return;
     }
     let Some(mod_name_ident) = mod_name_ident else {
         proc_macro_error::abort_call_site!("what, a module without a name?");
@@ -109,30 +462,190 @@ fn check_top_mod_on_error_abort(input_item: TokenStream) {
         proc_macro_error::abort!(
             mod_name_ident.span(),
             format!(
-                "{} {}",
+                "{} {} {} it applied to",
                 "this should always be `__`, to emphasis that",
-                "the attribute `mod_template::define` nullifies the name of the module it applied to"
+                "the attribute `mod_template::define` nullifies the name of the",
+                kind.description(),
             )
         )
     }
 }
 
+/// Makes a template of a trait `impl` block implement the trait for the
+/// placeholder struct generically, with the trait itself as a bound, so that
+/// the supertraits of the trait, which the placeholder struct doesn't
+/// implement, are assumed to be implemented.
+fn make_placeholder_trait_impl(input_item: TokenStream) -> TokenStream {
+    let Ok(mut item_impl) = syn::parse2::<syn::ItemImpl>(input_item.clone()) else {
+        return input_item;
+    };
+    let Some((None, trait_path, _)) = &item_impl.trait_ else {
+        return input_item;
+    };
+    let trait_path = trait_path.clone();
+    item_impl
+        .generics
+        .params
+        .insert(0, syn::parse_quote!(__Self: ?Sized));
+    item_impl.self_ty = syn::parse_quote!(__<__Self>);
+    item_impl
+        .generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote!(Self: #trait_path));
+
+    item_impl.into_token_stream()
+}
+
+/// Checks the options that don't apply to a template of a function or an
+/// `impl` block.
+///
+/// NOTE: the types and consts declared in the `types` and `consts` blocks are
+/// nameable only in the bodies of functions in an instance, as there is no
+/// module around it.
+fn check_non_mod_template(
+    input_item: TokenStream,
+    kind: TemplateKind,
+    opts: &AttributeOptions,
+) -> syn::Result<()> {
+    if let Some(path) = opts.path() {
+        return Err(syn::Error::new(
+            path.span(),
+            "the `path` option can only be used with a template of a module",
+        ));
+    }
+    let what = match kind {
+        TemplateKind::Impl => "an `impl` block",
+        _ => "a function",
+    };
+    if let Some(base_macro_path) = opts.extends() {
+        return Err(syn::Error::new_spanned(
+            base_macro_path,
+            format!("a template of {} can't extend another template", what),
+        ));
+    }
+
+    let outside_bodies = if kind == TemplateKind::Fn {
+        let item_fn: syn::ItemFn = syn::parse2(input_item)?;
+        item_fn.sig.to_token_stream()
+    } else {
+        let item_impl: syn::ItemImpl = syn::parse2(input_item)?;
+        if let Some(field) = find_self_field(&mut item_impl.clone()) {
+            return Err(syn::Error::new_spanned(
+                field,
+                "fields of `self` can't be used in a template of an `impl` block, since it's checked against a placeholder type without them. Use methods of a trait instead",
+            ));
+        }
+        let mut output = item_impl.generics.to_token_stream();
+        if let Some((_, trait_path, _)) = &item_impl.trait_ {
+            trait_path.to_tokens(&mut output);
+        }
+        for impl_item in &item_impl.items {
+            match impl_item {
+                syn::ImplItem::Fn(impl_item_fn) => impl_item_fn.sig.to_tokens(&mut output),
+                impl_item => impl_item.to_tokens(&mut output),
+            }
+        }
+        output
+    };
+    let target_name_idents = (opts.types().iter().map(|decl| decl.target_name_ident()))
+        .chain(opts.consts().iter().map(|decl| decl.target_name_ident()));
+    for target_name_ident in target_name_idents {
+        if mentions(outside_bodies.clone(), target_name_ident) {
+            return Err(syn::Error::new(
+                target_name_ident.span(),
+                format!(
+                    "`{}` can only be used in the bodies of functions in a template of {}",
+                    target_name_ident, what
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Finds a field of `self` used in the methods, e.g. `self.name` or `self.0`.
+fn find_self_field(item_impl: &mut syn::ItemImpl) -> Option<syn::ExprField> {
+    struct Finder(Option<syn::ExprField>);
+    impl VisitMut for Finder {
+        fn visit_expr_field_mut(&mut self, i: &mut syn::ExprField) {
+            let mut base = &*i.base;
+            while let syn::Expr::Paren(syn::ExprParen { expr, .. })
+            | syn::Expr::Unary(syn::ExprUnary {
+                op: syn::UnOp::Deref(..),
+                expr,
+                ..
+            }) = base
+            {
+                base = expr;
+            }
+            let is_self = matches!(
+                base,
+                syn::Expr::Path(expr_path) if expr_path.qself.is_none() && expr_path.path.is_ident("self")
+            );
+            if is_self && self.0.is_none() {
+                self.0 = Some(i.clone());
+            }
+            visit_mut::visit_expr_field_mut(self, i);
+        }
+    }
+
+    let mut finder = Finder(None);
+    finder.visit_item_impl_mut(item_impl);
+    finder.0
+}
+
+/// Loads the content of the template from the file specified by the `path`
+/// option, if any, and the submodules to be loaded from files.
+///
+/// Returns the template with those loaded, and the paths of the loaded files.
+fn load_files(
+    input_item: TokenStream,
+    opts: &AttributeOptions,
+) -> syn::Result<(TokenStream, Vec<PathBuf>)> {
+    let mut loaded_paths = vec![];
+    let mut dirs = template_dirs();
+    let mut item = input_item;
+    if let Some(path) = opts.path() {
+        let (loaded_item, template_path) = load_template_file(item, path, dirs.as_ref())?;
+        item = loaded_item;
+        // NOTE: like a module with the attribute `path`, the file is treated
+        // like a `mod.rs`.
+        dirs = Some(ModDirs::of_file(&template_path, true));
+        loaded_paths.push(template_path);
+    }
+    let (item, paths) = load_out_of_line_mods(item, dirs)?;
+    loaded_paths.extend(paths);
+
+    Ok((item, loaded_paths))
+}
+
 fn make_compiler_check_dummy_item_and_check_helper_attributes(
     input_item: TokenStream,
     opts: &AttributeOptions,
 ) -> Result<TokenStream, syn::Error> {
     let constructions = Rc::new(opts.build_type_map());
+    let defaulted_constructions: HashSet<String> = (opts.constructions().iter())
+        .filter(|decl| decl.default().is_some())
+        .map(|decl| decl.target_name_ident().to_string())
+        .collect();
 
     let attribute_substitutions = {
-        let attribute_substitutions = opts
-            .attribute_substitutions()
-            .iter()
-            .map(|item| item.target_name_ident().to_string());
-        let attribute_substitutions: HashSet<_> = HashSet::from_iter(attribute_substitutions);
+        let attribute_substitutions = opts.attribute_substitutions().iter().map(|item| {
+            (
+                item.target_name_ident().to_string(),
+                item.parameter_list_extension()
+                    .as_ref()
+                    .map(|ext| ext.to_token_stream()),
+            )
+        });
+        let attribute_substitutions: HashMap<_, _> = HashMap::from_iter(attribute_substitutions);
         Rc::new(attribute_substitutions)
     };
 
     let mut attr_map: HashMap<String, Box<Substituter>> = HashMap::new();
+    let crate_path = opts.crate_path();
     attr_map.insert(
         "__CONSTRUCT".to_string(),
         Box::new(move |meta| {
@@ -155,17 +668,32 @@ fn make_compiler_check_dummy_item_and_check_helper_attributes(
                     ));
                 };
                 let pat = construction.pattern_to_construct();
-                quote::quote!(
-                    #[::mod_template::construct(
-                        #pat: #ty = (|| -> #ty { unreachable!() })()
-                    )]
-                )
-                .to_tokens(&mut result);
+                // NOTE: default expressions are type-checked once instead, see
+                // `add_construction_default_checks`. Thus, the parameters they
+                // rely on may be unused here.
+                if defaulted_constructions.contains(&target_name) {
+                    quote::quote!(#[allow(unused_variables)]).to_tokens(&mut result);
+                }
+                if let syn::Type::ImplTrait(..) = ty {
+                    // NOTE: `impl` types are not allowed as the return type
+                    // of closures, and there is no concrete type to name here.
+                    // See `check_impl_type_constructions`.
+                    let marker_ident = Ident::new(IMPL_TYPE_CONSTRUCTION_MARKER, pat.span());
+                    quote::quote!(#[#marker_ident(#pat: #ty)]).to_tokens(&mut result);
+                } else {
+                    quote::quote!(
+                        #[#crate_path::construct(
+                            #pat: #ty = (|| -> #ty { unreachable!() })()
+                        )]
+                    )
+                    .to_tokens(&mut result);
+                }
             }
 
             Ok(result)
         }),
     );
+    let crate_path = opts.crate_path();
     attr_map.insert(
         "__SUBSTITUTE".to_string(),
         Box::new(move |meta| {
@@ -174,7 +702,7 @@ fn make_compiler_check_dummy_item_and_check_helper_attributes(
 
             let target_name_ident = opts.target_name_ident();
             let target_name = target_name_ident.to_string();
-            if !attribute_substitutions.contains(&target_name) {
+            let Some(ext) = attribute_substitutions.get(&target_name) else {
                 return Err(syn::Error::new(
                     target_name_ident.span(),
                     format!(
@@ -184,18 +712,79 @@ fn make_compiler_check_dummy_item_and_check_helper_attributes(
                         "among the options of the attribute `mod_template::define`"
                     ),
                 ));
-            }
+            };
 
-            Ok(quote::quote!())
+            Ok(match ext {
+                Some(ext) => quote::quote!(#[#crate_path::extend_parameter_list(#ext)]),
+                None => quote::quote!(),
+            })
         }),
     );
 
-    substitute_attributes(input_item, &mut attr_map)
+    // NOTE: the name of the macro is included, so that those symbols exported
+    // with `#[no_mangle]` won't collide between templates.
+    let macro_name_ident = opts.mbe_header().name_ident();
+    let ident_map: HashMap<String, Ident> = opts
+        .idents()
+        .iter()
+        .map(|decl| {
+            let target_name_ident = decl.target_name_ident();
+            let placeholder_ident = Ident::new(
+                &format!(
+                    "__mod_template__ident__{}__{}",
+                    macro_name_ident, target_name_ident
+                ),
+                target_name_ident.span(),
+            );
+            (target_name_ident.to_string(), placeholder_ident)
+        })
+        .collect();
+
+    let output = add_construction_default_checks(input_item, opts)?;
+    let output = substitute_attributes(output, &mut attr_map, &ident_map)?;
+    // NOTE: nested instances are left out, since the values forwarded to them
+    // are unknown here.
+    let forwarder = |kind: ForwardedKind, target_name_ident: &Ident| {
+        let is_declared = match kind {
+            ForwardedKind::Construction => opts
+                .constructions()
+                .iter()
+                .any(|decl| decl.target_name_ident() == target_name_ident),
+            ForwardedKind::AttributeSubstitution => opts
+                .attribute_substitutions()
+                .iter()
+                .any(|decl| decl.target_name_ident() == target_name_ident),
+        };
+        if !is_declared {
+            let which = match kind {
+                ForwardedKind::Construction => "constructions",
+                ForwardedKind::AttributeSubstitution => "attribute_substitutions",
+            };
+            return Err(syn::Error::new(
+                target_name_ident.span(),
+                format!(
+                    "unknown target name `{}`. {}{}{} {}",
+                    target_name_ident,
+                    "It should be declared in the `",
+                    which,
+                    "` block",
+                    "among the options of the attribute `mod_template::define`"
+                ),
+            ));
+        }
+        Ok(TokenStream::new())
+    };
+    let output = forward_into_nested_instances(output, &forwarder, false)?;
+    let output = check_impl_type_constructions(output, &opts.crate_path())?;
+    let output = inject_into_nested_mods(output, &make_placeholder_consts(opts));
+    let output = bind_placeholder_consts(output, opts)?;
+
+    make_type_parameters_generic(output, opts.types())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::define;
+    use super::{__compiler_check_dummy, define};
 
     #[test]
     fn basic() {
@@ -238,6 +827,18 @@ mod tests {
                 }
             }
             macro_rules! the_macro_name {
+                (@__mod_template__compiler_check_dummy { $($derived:tt)* }) => {
+                    #[::mod_template::__compiler_check_dummy((#input_opts) $($derived)*)]
+                    #input_item
+                };
+                (@__mod_template__extends { $($derived:tt)* } $($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                        $($derived)*
+                    )]
+                    #input_item
+                };
                 ($($input:tt)*) => {
                     #[::mod_template::__monomorphize_mod(
                         (#input_opts),
@@ -266,4 +867,792 @@ mod tests {
 
         assert_eq!(actual.to_string(), expected.to_string());
     }
+
+    #[test]
+    fn impl_type_construction() {
+        let input_opts = quote::quote!(
+            macro_rules! the_macro_name;
+            constructions(FOO -> impl Foo),
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                #[__CONSTRUCT(mut foo as FOO)]
+                fn an_fn() {}
+            }
+        };
+
+        let expected = quote::quote! {
+            #[cfg(test)]
+            #[allow(non_snake_case)]
+            mod __mod_template__compiler_check_dummy__the_macro_name {
+                mod __ {
+                    #[allow(dead_code, unused_variables, unused_mut)]
+                    fn an_fn() {
+                        unreachable!()
+                    }
+                    #[::mod_template::extend_parameter_list(.., mut foo: impl Foo)]
+                    #[allow(dead_code)]
+                    fn __mod_template__checked__an_fn() {}
+                }
+            }
+            macro_rules! the_macro_name {
+                (@__mod_template__compiler_check_dummy { $($derived:tt)* }) => {
+                    #[::mod_template::__compiler_check_dummy((#input_opts) $($derived)*)]
+                    #input_item
+                };
+                (@__mod_template__extends { $($derived:tt)* } $($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                        $($derived)*
+                    )]
+                    #input_item
+                };
+                ($($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                    )]
+                    mod __ {
+                        #[__CONSTRUCT(mut foo as FOO)]
+                        fn an_fn() {}
+                    }
+                }
+            }
+        };
+
+        let actual = define(input_opts, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn consts() {
+        let input_opts = quote::quote!(
+            macro_rules! the_macro_name;
+            consts(FOO: usize, BAR: &str, BAZ: Baz),
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                static BUFFER: [u8; FOO] = [0; FOO];
+
+                fn an_fn() {
+                    let _ = (BAR, BAZ);
+                }
+            }
+        };
+
+        let expected = quote::quote! {
+            #[cfg(test)]
+            #[allow(non_snake_case)]
+            mod __mod_template__compiler_check_dummy__the_macro_name {
+                mod __ {
+                    #[allow(dead_code)]
+                    const FOO: usize = 0;
+                    #[allow(dead_code)]
+                    const BAR: &str = "";
+                    static BUFFER: [u8; FOO] = [0; FOO];
+
+                    fn an_fn() {
+                        #[allow(non_snake_case, unused_variables)]
+                        let BAZ: Baz = (| | -> Baz { unreachable!() })();
+                        let _ = (BAR, BAZ);
+                    }
+                }
+            }
+            macro_rules! the_macro_name {
+                (@__mod_template__compiler_check_dummy { $($derived:tt)* }) => {
+                    #[::mod_template::__compiler_check_dummy((#input_opts) $($derived)*)]
+                    #input_item
+                };
+                (@__mod_template__extends { $($derived:tt)* } $($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                        $($derived)*
+                    )]
+                    #input_item
+                };
+                ($($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                    )]
+                    mod __ {
+                        static BUFFER: [u8; FOO] = [0; FOO];
+
+                        fn an_fn() {
+                            let _ = (BAR, BAZ);
+                        }
+                    }
+                }
+            }
+        };
+
+        let actual = define(input_opts, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn idents() {
+        let input_opts = quote::quote!(
+            macro_rules! the_macro_name;
+            idents(FOO),
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                #[no_mangle]
+                #[__IDENT(FOO)]
+                extern "C" fn foo() {}
+
+                fn an_fn() {
+                    __IDENT(FOO)();
+                }
+            }
+        };
+
+        let expected = quote::quote! {
+            #[cfg(test)]
+            #[allow(non_snake_case)]
+            mod __mod_template__compiler_check_dummy__the_macro_name {
+                mod __ {
+                    #[no_mangle]
+                    extern "C" fn __mod_template__ident__the_macro_name__FOO() {}
+
+                    fn an_fn() {
+                        __mod_template__ident__the_macro_name__FOO();
+                    }
+                }
+            }
+            macro_rules! the_macro_name {
+                (@__mod_template__compiler_check_dummy { $($derived:tt)* }) => {
+                    #[::mod_template::__compiler_check_dummy((#input_opts) $($derived)*)]
+                    #input_item
+                };
+                (@__mod_template__extends { $($derived:tt)* } $($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                        $($derived)*
+                    )]
+                    #input_item
+                };
+                ($($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                    )]
+                    #input_item
+                }
+            }
+        };
+
+        let actual = define(input_opts, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn construction_defaults() {
+        let input_opts = quote::quote!(
+            macro_rules! the_macro_name;
+            constructions(FOO -> i32 = 42),
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                #[__CONSTRUCT(foo as FOO)]
+                fn an_fn() {}
+            }
+        };
+
+        let expected = quote::quote! {
+            #[cfg(test)]
+            #[allow(non_snake_case)]
+            mod __mod_template__compiler_check_dummy__the_macro_name {
+                mod __ {
+                    #[allow(dead_code, non_snake_case, unused_variables)]
+                    fn __mod_template__default__FOO() -> i32 {
+                        42
+                    }
+                    #[allow(unused_variables)]
+                    #[::mod_template::construct(foo: i32 = (|| -> i32 { unreachable!() })())]
+                    fn an_fn() {}
+                }
+            }
+            macro_rules! the_macro_name {
+                (@__mod_template__compiler_check_dummy { $($derived:tt)* }) => {
+                    #[::mod_template::__compiler_check_dummy((#input_opts) $($derived)*)]
+                    #input_item
+                };
+                (@__mod_template__extends { $($derived:tt)* } $($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                        $($derived)*
+                    )]
+                    #input_item
+                };
+                ($($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                    )]
+                    mod __ {
+                        #[__CONSTRUCT(foo as FOO)]
+                        fn an_fn() {}
+                    }
+                }
+            }
+        };
+
+        let actual = define(input_opts, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn declared_parameter_list_extensions() {
+        let input_opts = quote::quote!(
+            macro_rules! the_macro_name;
+            constructions(FOO -> i32),
+            attribute_substitutions(BAR(.., n: i32)),
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                #[__CONSTRUCT(foo as FOO)]
+                #[__SUBSTITUTE(BAR)]
+                fn an_fn() {}
+            }
+        };
+
+        let expected = quote::quote! {
+            #[cfg(test)]
+            #[allow(non_snake_case)]
+            mod __mod_template__compiler_check_dummy__the_macro_name {
+                mod __ {
+                    #[::mod_template::construct(foo: i32 = (|| -> i32 { unreachable!() })())]
+                    #[::mod_template::extend_parameter_list(.., n: i32)]
+                    fn an_fn() {}
+                }
+            }
+            macro_rules! the_macro_name {
+                (@__mod_template__compiler_check_dummy { $($derived:tt)* }) => {
+                    #[::mod_template::__compiler_check_dummy((#input_opts) $($derived)*)]
+                    #input_item
+                };
+                (@__mod_template__extends { $($derived:tt)* } $($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                        $($derived)*
+                    )]
+                    #input_item
+                };
+                ($($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                    )]
+                    mod __ {
+                        #[__CONSTRUCT(foo as FOO)]
+                        #[__SUBSTITUTE(BAR)]
+                        fn an_fn() {}
+                    }
+                }
+            }
+        };
+
+        let actual = define(input_opts, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn extends() {
+        let input_opts = quote::quote!(
+            macro_rules! the_macro_name;
+            extends(the_base_macro_name),
+            constructions(FOO -> i32),
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                #[__CONSTRUCT(foo as FOO)]
+                fn an_fn() {}
+            }
+        };
+
+        let expected = quote::quote! {
+            the_base_macro_name! {
+                @__mod_template__compiler_check_dummy { ((#input_opts) #input_item) }
+            }
+
+            macro_rules! the_macro_name {
+                (@__mod_template__compiler_check_dummy { $($derived:tt)* }) => {
+                    the_base_macro_name! {
+                        @__mod_template__compiler_check_dummy {
+                            ((#input_opts) #input_item) $($derived)*
+                        }
+                    }
+                };
+                (@__mod_template__extends { $($derived:tt)* } $($input:tt)*) => {
+                    the_base_macro_name! {
+                        @__mod_template__extends { ((#input_opts) #input_item) $($derived)* }
+                        $($input)*
+                    }
+                };
+                ($($input:tt)*) => {
+                    the_base_macro_name! {
+                        @__mod_template__extends { ((#input_opts) #input_item) }
+                        $($input)*
+                    }
+                };
+            }
+        };
+
+        let actual = define(input_opts, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn compiler_check_dummy_of_extending_template() {
+        let input_attr = quote::quote!(
+            (macro_rules! the_base_macro_name; constructions(FOO -> i32))
+            ((macro_rules! the_macro_name; extends(the_base_macro_name), constructions(BAR -> i32)) mod __ {
+                #[__CONSTRUCT(bar as BAR)]
+                fn another_fn() {}
+            })
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                #[__CONSTRUCT(foo as FOO)]
+                fn an_fn() {}
+            }
+        };
+
+        let expected = quote::quote! {
+            #[cfg(test)]
+            #[allow(non_snake_case, dead_code)]
+            mod __mod_template__compiler_check_dummy__the_macro_name {
+                mod __ {
+                    #[::mod_template::construct(foo: i32 = (|| -> i32 { unreachable!() })())]
+                    fn an_fn() {}
+                    #[::mod_template::construct(bar: i32 = (|| -> i32 { unreachable!() })())]
+                    fn another_fn() {}
+                }
+            }
+        };
+
+        let actual = __compiler_check_dummy(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn compiler_check_dummy_of_extending_template_with_unknown_target_names() {
+        let input_attr = quote::quote!(
+            (macro_rules! the_base_macro_name; constructions(FOO -> i32))
+            ((macro_rules! the_macro_name; extends(the_base_macro_name),) mod __ {
+                #[__CONSTRUCT(bar as BAR)]
+                fn another_fn() {}
+            })
+        );
+        let input_item = quote::quote! {
+            mod __ {}
+        };
+
+        let expected = quote::quote! {
+            ::core::compile_error! { "unknown target name `BAR`. It should be declared in the `constructions` block among the options of the attribute `mod_template::define`" }
+        };
+
+        let actual = __compiler_check_dummy(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn nested_instances() {
+        let input_opts = quote::quote!(
+            macro_rules! the_macro_name;
+            constructions(FOO -> i32),
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                #[__NESTED]
+                define_another_mod! {
+                    mod a_nested_mod;
+                    constructions { BAR => __FORWARD(FOO) },
+                }
+            }
+        };
+
+        let expected = quote::quote! {
+            #[cfg(test)]
+            #[allow(non_snake_case)]
+            mod __mod_template__compiler_check_dummy__the_macro_name {
+                mod __ {
+                    const _: () = ();
+                }
+            }
+            macro_rules! the_macro_name {
+                (@__mod_template__compiler_check_dummy { $($derived:tt)* }) => {
+                    #[::mod_template::__compiler_check_dummy((#input_opts) $($derived)*)]
+                    #input_item
+                };
+                (@__mod_template__extends { $($derived:tt)* } $($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                        $($derived)*
+                    )]
+                    #input_item
+                };
+                ($($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                    )]
+                    #input_item
+                }
+            }
+        };
+
+        let actual = define(input_opts, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn dollars() {
+        let input_opts = quote::quote!(macro_rules! the_macro_name;);
+        let input_item = quote::quote! {
+            mod __ {
+                macro_rules! a_macro {
+                    ($($x:expr),*) => {};
+                }
+            }
+        };
+
+        let escaped_item = quote::quote! {
+            mod __ {
+                macro_rules! a_macro {
+                    ($dollar($dollar x:expr),*) => {};
+                }
+            }
+        };
+        let expected = quote::quote! {
+            #[cfg(test)]
+            #[allow(non_snake_case)]
+            mod __mod_template__compiler_check_dummy__the_macro_name {
+                #input_item
+            }
+            macro_rules! the_macro_name {
+                (@__mod_template__dollar ($dollar:tt) @__mod_template__compiler_check_dummy { $($derived:tt)* }) => {
+                    #[::mod_template::__compiler_check_dummy((#input_opts) $($derived)*)]
+                    #escaped_item
+                };
+                (@__mod_template__dollar ($dollar:tt) @__mod_template__extends { $($derived:tt)* } $($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                        $($derived)*
+                    )]
+                    #escaped_item
+                };
+                (@__mod_template__dollar ($dollar:tt) $($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                    )]
+                    #escaped_item
+                };
+                ($($input:tt)*) => {
+                    the_macro_name! { @__mod_template__dollar ($) $($input)* }
+                };
+            }
+        };
+
+        let actual = define(input_opts, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn exported_dollars() {
+        let input_opts = quote::quote!(#[macro_export] macro_rules! the_macro_name;);
+        let input_item = quote::quote! {
+            mod __ {
+                macro_rules! a_macro {
+                    ($($x:expr),*) => {};
+                }
+            }
+        };
+
+        let escaped_item = quote::quote! {
+            mod __ {
+                macro_rules! a_macro {
+                    ($dollar($dollar x:expr),*) => {};
+                }
+            }
+        };
+        let expected = quote::quote! {
+            #[cfg(test)]
+            #[allow(non_snake_case)]
+            mod __mod_template__compiler_check_dummy__the_macro_name {
+                #input_item
+            }
+            #[macro_export]
+            macro_rules! the_macro_name {
+                (@__mod_template__dollar ($dollar:tt) @__mod_template__compiler_check_dummy { $($derived:tt)* }) => {
+                    #[::mod_template::__compiler_check_dummy((#input_opts) $($derived)*)]
+                    #escaped_item
+                };
+                (@__mod_template__dollar ($dollar:tt) @__mod_template__extends { $($derived:tt)* } $($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                        $($derived)*
+                    )]
+                    #escaped_item
+                };
+                (@__mod_template__dollar ($dollar:tt) $($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                    )]
+                    #escaped_item
+                };
+                ($($input:tt)*) => {
+                    $crate::the_macro_name! { @__mod_template__dollar ($) $($input)* }
+                };
+            }
+        };
+
+        let actual = define(input_opts, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn crate_placeholders() {
+        let input_opts = quote::quote!(
+            macro_rules! the_macro_name;
+            constructions(FOO -> __CRATE::Foo),
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                #[__CONSTRUCT(foo as FOO)]
+                fn an_fn() {
+                    __CRATE::bar(foo);
+                }
+            }
+        };
+
+        let output_opts = quote::quote!(
+            macro_rules! the_macro_name;
+            constructions(FOO -> $crate::Foo),
+        );
+        let output_item = quote::quote! {
+            mod __ {
+                #[__CONSTRUCT(foo as FOO)]
+                fn an_fn() {
+                    $crate::bar(foo);
+                }
+            }
+        };
+        let expected = quote::quote! {
+            #[cfg(test)]
+            #[allow(non_snake_case)]
+            mod __mod_template__compiler_check_dummy__the_macro_name {
+                mod __ {
+                    #[::mod_template::construct(foo: crate::Foo = (|| -> crate::Foo { unreachable!() })())]
+                    fn an_fn() {
+                        crate::bar(foo);
+                    }
+                }
+            }
+            macro_rules! the_macro_name {
+                (@__mod_template__compiler_check_dummy { $($derived:tt)* }) => {
+                    #[::mod_template::__compiler_check_dummy((#output_opts) $($derived)*)]
+                    #output_item
+                };
+                (@__mod_template__extends { $($derived:tt)* } $($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#output_opts),
+                        { $($input)* }
+                        $($derived)*
+                    )]
+                    #output_item
+                };
+                ($($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#output_opts),
+                        { $($input)* }
+                    )]
+                    #output_item
+                }
+            }
+        };
+
+        let actual = define(input_opts, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn crate_path() {
+        let input_opts = quote::quote!(
+            macro_rules! the_macro_name;
+            crate = ::a_facade::mod_template,
+            constructions(FOO -> i32),
+        );
+        let input_item = quote::quote! {
+            mod __ {
+                #[__CONSTRUCT(foo as FOO)]
+                fn an_fn() {}
+            }
+        };
+
+        let expected = quote::quote! {
+            #[cfg(test)]
+            #[allow(non_snake_case)]
+            mod __mod_template__compiler_check_dummy__the_macro_name {
+                mod __ {
+                    #[::a_facade::mod_template::construct(foo: i32 = (|| -> i32 { unreachable!() })())]
+                    fn an_fn() {}
+                }
+            }
+            macro_rules! the_macro_name {
+                (@__mod_template__compiler_check_dummy { $($derived:tt)* }) => {
+                    #[::a_facade::mod_template::__compiler_check_dummy((#input_opts) $($derived)*)]
+                    #input_item
+                };
+                (@__mod_template__extends { $($derived:tt)* } $($input:tt)*) => {
+                    #[::a_facade::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                        $($derived)*
+                    )]
+                    #input_item
+                };
+                ($($input:tt)*) => {
+                    #[::a_facade::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                    )]
+                    #input_item
+                }
+            }
+        };
+
+        let actual = define(input_opts, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn fn_template() {
+        let input_opts = quote::quote!(
+            macro_rules! the_macro_name;
+            constructions(FOO -> i32),
+        );
+        let input_item = quote::quote! {
+            #[__CONSTRUCT(foo as FOO)]
+            fn __() {}
+        };
+
+        let expected = quote::quote! {
+            #[cfg(test)]
+            #[allow(non_snake_case)]
+            mod __mod_template__compiler_check_dummy__the_macro_name {
+                mod __ {
+                    #[::mod_template::construct(foo: i32 = (|| -> i32 { unreachable!() })())]
+                    fn __() {}
+                }
+            }
+            macro_rules! the_macro_name {
+                (@__mod_template__compiler_check_dummy { $($derived:tt)* }) => {
+                    #[::mod_template::__compiler_check_dummy((#input_opts) $($derived)*)]
+                    #input_item
+                };
+                (@__mod_template__extends { $($derived:tt)* } $($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                        $($derived)*
+                    )]
+                    #input_item
+                };
+                ($($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                    )]
+                    #input_item
+                }
+            }
+        };
+
+        let actual = define(input_opts, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn impl_template() {
+        let input_opts = quote::quote!(
+            macro_rules! the_macro_name;
+            constructions(FOO -> i32),
+        );
+        let input_item = quote::quote! {
+            impl crate::Trait for __ {
+                #[__CONSTRUCT(foo as FOO)]
+                fn an_fn(&self) {}
+            }
+        };
+
+        let expected = quote::quote! {
+            #[cfg(test)]
+            #[allow(non_snake_case)]
+            mod __mod_template__compiler_check_dummy__the_macro_name {
+                mod __ {
+                    #[allow(dead_code)]
+                    struct __<__Self: ?Sized = ()>(::core::marker::PhantomData<__Self>);
+                    impl<__Self: ?Sized> crate::Trait for __<__Self>
+                    where
+                        Self: crate::Trait
+                    {
+                        #[::mod_template::construct(foo: i32 = (|| -> i32 { unreachable!() })())]
+                        fn an_fn(&self) {}
+                    }
+                }
+            }
+            macro_rules! the_macro_name {
+                (@__mod_template__compiler_check_dummy { $($derived:tt)* }) => {
+                    #[::mod_template::__compiler_check_dummy((#input_opts) $($derived)*)]
+                    #input_item
+                };
+                (@__mod_template__extends { $($derived:tt)* } $($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                        $($derived)*
+                    )]
+                    #input_item
+                };
+                ($($input:tt)*) => {
+                    #[::mod_template::__monomorphize_mod(
+                        (#input_opts),
+                        { $($input)* }
+                    )]
+                    #input_item
+                }
+            }
+        };
+
+        let actual = define(input_opts, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
 }
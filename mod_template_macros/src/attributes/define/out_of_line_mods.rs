@@ -0,0 +1,460 @@
+use std::path::{Path, PathBuf};
+
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{spanned::Spanned, visit_mut::VisitMut};
+
+const MARKER: &str = "__FROM_FILE";
+
+const UNKNOWN_TEMPLATE_DIR_MESSAGE: &str =
+    "can't locate the source file of the template to load modules relative to it";
+
+/// The directories to load the files of modules from, following the rules of
+/// rustc.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModDirs {
+    /// Where the files with explicit paths (e.g.
+    /// `#[__FROM_FILE("path/to/file.rs")]`) are relative to.
+    explicit: PathBuf,
+    /// Where the files of other modules (e.g. `mod foo;`) are, which is a
+    /// directory named after the current file, unless the latter is a
+    /// `mod.rs` or something like it.
+    implicit: PathBuf,
+}
+
+impl ModDirs {
+    /// The directories for the modules in the file at `file_path`. The files
+    /// loaded from explicit paths, the roots of crates, and the files named
+    /// `mod.rs` are treated like `mod.rs`.
+    pub fn of_file(file_path: &Path, is_mod_rs: bool) -> Self {
+        let dir = file_path.parent().unwrap_or(Path::new("")).to_path_buf();
+        let stem = file_path.file_stem().filter(|_| !is_mod_rs);
+        Self {
+            implicit: match stem {
+                Some(stem) => dir.join(stem),
+                None => dir.clone(),
+            },
+            explicit: dir,
+        }
+    }
+
+    /// The directories for the modules in an inline module named `name`.
+    fn join(&self, name: &str) -> Self {
+        let dir = self.implicit.join(name);
+        Self {
+            explicit: dir.clone(),
+            implicit: dir,
+        }
+    }
+}
+
+/// Loads the content of the template from the file at `path`, relative to
+/// `dirs`. The tokens from the file are spanned at `path`.
+///
+/// Returns the template with its content, and the path of the loaded file.
+pub fn load_template_file(
+    input_item: TokenStream,
+    path: &syn::LitStr,
+    dirs: Option<&ModDirs>,
+) -> syn::Result<(TokenStream, PathBuf)> {
+    let mut item_mod: syn::ItemMod = syn::parse2(input_item)?;
+    if let Some((_, items)) = &item_mod.content {
+        if !items.is_empty() {
+            return Err(syn::Error::new(
+                item_mod.ident.span(),
+                "the template should be empty when it's loaded from a file by the `path` option",
+            ));
+        }
+    }
+    let Some(dirs) = dirs else {
+        return Err(syn::Error::new(path.span(), UNKNOWN_TEMPLATE_DIR_MESSAGE));
+    };
+
+    let file_path = dirs.explicit.join(path.value());
+    let file = read_file(&file_path, path.span())?;
+    item_mod.attrs.extend(file.attrs);
+    item_mod.content = Some((Default::default(), file.items));
+    item_mod.semi = None;
+
+    Ok((item_mod.into_token_stream(), canonicalize(file_path)))
+}
+
+/// Loads the content of every module in the template that is marked with
+/// `#[__FROM_FILE]` (or `#[__FROM_FILE("path/to/file.rs")]`), or that is
+/// out-of-line (`mod foo;`), from files relative to `dirs`.
+///
+/// NOTE: rustc doesn't allow out-of-line modules in the input of attribute
+/// macros on stable, hence the marker.
+///
+/// Returns the template with those modules inlined, and the paths of the
+/// loaded files.
+pub fn load_out_of_line_mods(
+    input_item: TokenStream,
+    dirs: Option<ModDirs>,
+) -> syn::Result<(TokenStream, Vec<PathBuf>)> {
+    let mut item_mod: syn::ItemMod = syn::parse2(input_item)?;
+    let Some((_, items)) = &mut item_mod.content else {
+        return Err(syn::Error::new(
+            item_mod.semi.span(),
+            "the template itself can't be out-of-line. Use `mod __ {}` with the `path` option to load it from a file",
+        ));
+    };
+
+    let mut loader = Loader {
+        dirs,
+        loaded_paths: vec![],
+        err: None,
+    };
+    for item in items {
+        loader.visit_item_mut(item);
+    }
+
+    match loader.err {
+        Some(err) => Err(err),
+        None => Ok((item_mod.into_token_stream(), loader.loaded_paths)),
+    }
+}
+
+/// The directories for the modules in the source file containing the template,
+/// if available.
+pub fn template_dirs() -> Option<ModDirs> {
+    if !proc_macro::is_available() {
+        return None;
+    }
+    let file = proc_macro::Span::call_site().local_file()?;
+    let is_mod_rs = ["mod.rs", "lib.rs", "main.rs"]
+        .iter()
+        .any(|name| file.file_name() == Some(name.as_ref()))
+        || is_crate_root(&file);
+    Some(ModDirs::of_file(&file, is_mod_rs))
+}
+
+/// Whether the file is the root of the crate being compiled, e.g. `tests/foo.rs`
+/// for the test `foo`, which is treated like a `mod.rs` by rustc.
+///
+/// NOTE: there is no API for this, but the macro runs in the process of rustc,
+/// which takes the path of the root as an argument.
+fn is_crate_root(file: &Path) -> bool {
+    let file = canonicalize(file.to_path_buf());
+    std::env::args_os()
+        .filter(|arg| Path::new(arg).extension() == Some("rs".as_ref()))
+        .any(|arg| canonicalize(PathBuf::from(arg)) == file)
+}
+
+struct Loader {
+    /// Where the files of the modules at the current level are.
+    dirs: Option<ModDirs>,
+    loaded_paths: Vec<PathBuf>,
+    err: Option<syn::Error>,
+}
+
+impl Loader {
+    /// Returns the directories of the submodules of the loaded module.
+    fn load(
+        &mut self,
+        item_mod: &mut syn::ItemMod,
+        path: Option<String>,
+        span: Span,
+    ) -> syn::Result<ModDirs> {
+        let Some(dirs) = &self.dirs else {
+            return Err(syn::Error::new(span, UNKNOWN_TEMPLATE_DIR_MESSAGE));
+        };
+        if let Some((_, items)) = &item_mod.content {
+            if !items.is_empty() {
+                return Err(syn::Error::new(
+                    span,
+                    "a module loaded from a file should be empty in the template",
+                ));
+            }
+        }
+
+        let name = item_mod.ident.to_string();
+        let is_explicit = path.is_some();
+        let file_path = match path {
+            Some(path) => dirs.explicit.join(path),
+            None => {
+                let candidates = [
+                    dirs.implicit.join(format!("{}.rs", name)),
+                    dirs.implicit.join(&name).join("mod.rs"),
+                ];
+                match candidates.iter().find(|path| path.is_file()) {
+                    Some(path) => path.clone(),
+                    None => {
+                        return Err(syn::Error::new(
+                            span,
+                            format!(
+                                "file not found for module `{}`. It should be either `{}` or `{}`",
+                                name,
+                                candidates[0].display(),
+                                candidates[1].display(),
+                            ),
+                        ))
+                    }
+                }
+            }
+        };
+
+        let file = read_file(&file_path, span)?;
+        item_mod.attrs.extend(file.attrs);
+        item_mod.content = Some((Default::default(), file.items));
+        item_mod.semi = None;
+        self.loaded_paths.push(canonicalize(file_path.clone()));
+
+        let is_mod_rs = is_explicit || file_path.file_name() == Some("mod.rs".as_ref());
+        // NOTE: the file is named after the module unless it's loaded from an
+        // explicit path, so the stem of the file is the name of the module.
+        Ok(ModDirs::of_file(&file_path, is_mod_rs))
+    }
+}
+
+impl VisitMut for Loader {
+    fn visit_item_mod_mut(&mut self, item_mod: &mut syn::ItemMod) {
+        let marker_index = (item_mod.attrs.iter()).position(|attr| attr.path().is_ident(MARKER));
+        let result = match marker_index {
+            Some(index) => {
+                let marker = item_mod.attrs.remove(index);
+                let path = match &marker.meta {
+                    syn::Meta::Path(..) => Ok(None),
+                    syn::Meta::List(list) => list
+                        .parse_args::<syn::LitStr>()
+                        .map(|path| Some(path.value())),
+                    syn::Meta::NameValue(..) => Err(syn::Error::new(
+                        marker.span(),
+                        "expected `#[__FROM_FILE]` or `#[__FROM_FILE(\"path/to/file.rs\")]`",
+                    )),
+                };
+                path.and_then(|path| self.load(item_mod, path, marker.path().span()))
+                    .map(Some)
+            }
+            None if item_mod.content.is_none() => {
+                let span = item_mod.ident.span();
+                self.load(item_mod, None, span).map(Some)
+            }
+            None => Ok(None),
+        };
+
+        let sub_dirs = match result {
+            Ok(Some(sub_dirs)) => Some(sub_dirs),
+            Ok(None) => (self.dirs.as_ref()).map(|dirs| dirs.join(&item_mod.ident.to_string())),
+            Err(err) => {
+                match self.err {
+                    Some(ref mut self_err) => self_err.combine(err),
+                    None => self.err = Some(err),
+                }
+                return;
+            }
+        };
+
+        let dirs = std::mem::replace(&mut self.dirs, sub_dirs);
+        syn::visit_mut::visit_item_mod_mut(self, item_mod);
+        self.dirs = dirs;
+    }
+}
+
+/// Reads and parses a file, with its tokens spanned at `span`, since there is
+/// no way to make spans in other files on stable.
+fn read_file(file_path: &Path, span: Span) -> syn::Result<syn::File> {
+    let content = std::fs::read_to_string(file_path).map_err(|err| {
+        syn::Error::new(
+            span,
+            format!("failed to read `{}`: {}", file_path.display(), err),
+        )
+    })?;
+    let parse_err = |err: &dyn std::fmt::Display| {
+        syn::Error::new(
+            span,
+            format!("failed to parse `{}`: {}", file_path.display(), err),
+        )
+    };
+    let tokens: TokenStream = content.parse().map_err(|err| parse_err(&err))?;
+    syn::parse2(respan(tokens, span)).map_err(|err| parse_err(&err))
+}
+
+fn respan(input: TokenStream, span: Span) -> TokenStream {
+    input
+        .into_iter()
+        .map(|mut tt| {
+            if let TokenTree::Group(group) = &tt {
+                tt = TokenTree::Group(Group::new(group.delimiter(), respan(group.stream(), span)));
+            }
+            tt.set_span(span);
+            tt
+        })
+        .collect()
+}
+
+/// NOTE: the path from rustc may be relative to its working directory.
+fn canonicalize(path: PathBuf) -> PathBuf {
+    std::fs::canonicalize(&path).unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_out_of_line_mods, load_template_file, ModDirs};
+
+    #[test]
+    fn without_out_of_line_mods() {
+        let input = quote::quote! {
+            mod __ {
+                fn an_fn() {}
+                mod a_sub_mod {
+                    fn another_fn() {}
+                }
+            }
+        };
+
+        let (actual, loaded_paths) = load_out_of_line_mods(input.clone(), None).unwrap();
+
+        assert_eq!(actual.to_string(), input.to_string());
+        assert!(loaded_paths.is_empty());
+    }
+
+    #[test]
+    fn basic() {
+        let dir = std::env::temp_dir().join(format!(
+            "mod_template__out_of_line_mods__{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(dir.join("a_sub_mod")).unwrap();
+        std::fs::write(
+            dir.join("a_sub_mod.rs"),
+            "fn an_fn() {} #[__FROM_FILE] mod a_sub_sub_mod {}",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("a_sub_mod").join("a_sub_sub_mod.rs"),
+            "fn another_fn() {}",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("elsewhere.rs"),
+            "#![allow(unused)] fn yet_another_fn() {}",
+        )
+        .unwrap();
+
+        let input = quote::quote! {
+            mod __ {
+                #[__FROM_FILE]
+                mod a_sub_mod {}
+                #[__FROM_FILE("elsewhere.rs")]
+                mod another_sub_mod {}
+            }
+        };
+
+        let expected = quote::quote! {
+            mod __ {
+                mod a_sub_mod {
+                    fn an_fn() {}
+                    mod a_sub_sub_mod {
+                        fn another_fn() {}
+                    }
+                }
+                mod another_sub_mod {
+                    #![allow(unused)]
+                    fn yet_another_fn() {}
+                }
+            }
+        };
+
+        let dirs = ModDirs::of_file(&dir.join("lib.rs"), true);
+        let (actual, loaded_paths) = load_out_of_line_mods(input, Some(dirs)).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(actual.to_string(), expected.to_string());
+        assert_eq!(loaded_paths.len(), 3);
+    }
+
+    #[test]
+    fn non_mod_rs() {
+        let dir =
+            std::env::temp_dir().join(format!("mod_template__non_mod_rs__{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("foo").join("inline")).unwrap();
+        std::fs::write(dir.join("foo").join("a_sub_mod.rs"), "fn an_fn() {}").unwrap();
+        std::fs::write(
+            dir.join("foo").join("inline").join("another_sub_mod.rs"),
+            "fn another_fn() {}",
+        )
+        .unwrap();
+        std::fs::write(dir.join("elsewhere.rs"), "fn yet_another_fn() {}").unwrap();
+
+        let input = quote::quote! {
+            mod __ {
+                mod a_sub_mod;
+                mod inline {
+                    mod another_sub_mod;
+                }
+                #[__FROM_FILE("elsewhere.rs")]
+                mod yet_another_sub_mod {}
+            }
+        };
+
+        let expected = quote::quote! {
+            mod __ {
+                mod a_sub_mod {
+                    fn an_fn() {}
+                }
+                mod inline {
+                    mod another_sub_mod {
+                        fn another_fn() {}
+                    }
+                }
+                mod yet_another_sub_mod {
+                    fn yet_another_fn() {}
+                }
+            }
+        };
+
+        let dirs = ModDirs::of_file(&dir.join("foo.rs"), false);
+        let result = load_out_of_line_mods(input, Some(dirs));
+        std::fs::remove_dir_all(&dir).unwrap();
+        let (actual, loaded_paths) = result.unwrap();
+
+        assert_eq!(actual.to_string(), expected.to_string());
+        assert_eq!(loaded_paths.len(), 3);
+    }
+
+    #[test]
+    fn without_template_dir() {
+        let input = quote::quote! {
+            mod __ {
+                #[__FROM_FILE]
+                mod a_sub_mod {}
+            }
+        };
+
+        assert!(load_out_of_line_mods(input, None).is_err());
+    }
+
+    #[test]
+    fn template_file() {
+        let dir = std::env::temp_dir().join(format!(
+            "mod_template__template_file__{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(dir.join("templates")).unwrap();
+        std::fs::write(
+            dir.join("templates").join("foo.rs"),
+            "#![allow(unused)] fn an_fn() {}",
+        )
+        .unwrap();
+
+        let input = quote::quote!(
+            mod __ {}
+        );
+        let path: syn::LitStr = syn::parse_quote!("templates/foo.rs");
+
+        let expected = quote::quote! {
+            mod __ {
+                #![allow(unused)]
+                fn an_fn() {}
+            }
+        };
+
+        let dirs = ModDirs::of_file(&dir.join("lib.rs"), true);
+        let (actual, loaded_path) = load_template_file(input, &path, Some(&dirs)).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(actual.to_string(), expected.to_string());
+        assert!(loaded_path.ends_with("templates/foo.rs"));
+    }
+}
@@ -0,0 +1,117 @@
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn::visit_mut::{self, VisitMut};
+
+use super::{type_parameters::mentions, AttributeOptions};
+
+/// Consts declared in the `consts` block with primitive types (integers,
+/// floats, `bool`, `char` and `&str`), with zeros, `false`, `'\0'` or empty
+/// strings as their values, so that they can also be used in constant
+/// evaluations (e.g. array lengths).
+///
+/// NOTE: const items are always evaluated, even if they are unused, so the
+/// values can't be something like `unreachable!()`. Consts of other types are
+/// bound in function bodies instead, see `bind_placeholder_consts`.
+pub fn make_placeholder_consts(opts: &AttributeOptions) -> TokenStream {
+    let mut output = TokenStream::new();
+    for decl in opts.consts() {
+        let target_name_ident = decl.target_name_ident();
+        let ty = decl.ty();
+        let Some(value) = primitive_placeholder(ty) else {
+            continue;
+        };
+        quote::quote!(
+            #[allow(dead_code)]
+            const #target_name_ident: #ty = #value;
+        )
+        .to_tokens(&mut output);
+    }
+    output
+}
+
+/// Binds the consts declared in the `consts` block with non-primitive types to
+/// `unreachable!()`-style placeholder values, at the beginning of the bodies of
+/// the functions mentioning them, like constructions.
+pub fn bind_placeholder_consts(
+    input_item: TokenStream,
+    opts: &AttributeOptions,
+) -> Result<TokenStream, syn::Error> {
+    let bindings: Vec<(syn::Ident, syn::Stmt)> = opts
+        .consts()
+        .iter()
+        .filter(|decl| primitive_placeholder(decl.ty()).is_none())
+        .map(|decl| {
+            let target_name_ident = decl.target_name_ident();
+            let ty = decl.ty();
+            let stmt: syn::Stmt = syn::parse_quote!(
+                #[allow(non_snake_case, unused_variables)]
+                let #target_name_ident: #ty = (|| -> #ty { unreachable!() })();
+            );
+            (target_name_ident.clone(), stmt)
+        })
+        .collect();
+    if bindings.is_empty() {
+        return Ok(input_item);
+    }
+
+    struct Binder {
+        bindings: Vec<(syn::Ident, syn::Stmt)>,
+    }
+    impl Binder {
+        fn bind(&self, block: &mut syn::Block) {
+            let tokens = block.to_token_stream();
+            let stmts = (self.bindings.iter())
+                .filter(|(target_name_ident, _)| mentions(tokens.clone(), target_name_ident))
+                .map(|(_, stmt)| stmt.clone());
+            block.stmts.splice(0..0, stmts);
+        }
+    }
+    impl VisitMut for Binder {
+        fn visit_item_fn_mut(&mut self, i: &mut syn::ItemFn) {
+            self.bind(&mut i.block);
+            visit_mut::visit_item_fn_mut(self, i);
+        }
+        fn visit_impl_item_fn_mut(&mut self, i: &mut syn::ImplItemFn) {
+            self.bind(&mut i.block);
+            visit_mut::visit_impl_item_fn_mut(self, i);
+        }
+        fn visit_trait_item_fn_mut(&mut self, i: &mut syn::TraitItemFn) {
+            if let Some(block) = &mut i.default {
+                self.bind(block);
+            }
+            visit_mut::visit_trait_item_fn_mut(self, i);
+        }
+    }
+
+    let mut item: syn::ItemMod = syn::parse2(input_item)?;
+    Binder { bindings }.visit_item_mod_mut(&mut item);
+
+    Ok(item.into_token_stream())
+}
+
+/// The placeholder value of a const of a primitive type.
+fn primitive_placeholder(ty: &syn::Type) -> Option<TokenStream> {
+    match ty {
+        syn::Type::Path(type_path) if type_path.qself.is_none() => {
+            let ident = type_path.path.get_ident()?.to_string();
+            match ident.as_str() {
+                "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64"
+                | "i128" | "isize" => Some(quote::quote!(0)),
+                "f32" | "f64" => Some(quote::quote!(0.0)),
+                "bool" => Some(quote::quote!(false)),
+                "char" => Some(quote::quote!('\0')),
+                _ => None,
+            }
+        }
+        syn::Type::Reference(reference) if reference.mutability.is_none() => {
+            match &*reference.elem {
+                syn::Type::Path(type_path) if type_path.path.is_ident("str") => {
+                    Some(quote::quote!(""))
+                }
+                _ => None,
+            }
+        }
+        syn::Type::Paren(paren) => primitive_placeholder(&paren.elem),
+        _ => None,
+    }
+}
@@ -0,0 +1,1054 @@
+use std::collections::HashMap;
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{
+    punctuated::Punctuated,
+    visit_mut::{self, VisitMut},
+};
+
+use super::TypeDeclaration;
+
+/// Makes the compiler check dummy module type-check the template against the
+/// bounds declared in the `types` block, without knowing the actual types.
+///
+/// Structs, enums, unions, type aliases, traits and functions that mention a
+/// declared type, directly or through one another, get it as a generic
+/// parameter, and the paths to them get it as a generic argument, so that
+/// callers of such functions don't rely on type inference. `impl` blocks of
+/// those items get it as well, and so do the methods that mention a declared
+/// type or one of those items. Everywhere else (e.g. consts and statics), the
+/// declared type resolves to an uninhabited placeholder enum defined in each
+/// module that doesn't define or import an item of the same name.
+///
+/// Names are resolved through the modules and blocks of the template, along
+/// with their `use` declarations, glob imports included. Paths starting with
+/// `crate` or `::`, and names that can't be resolved, are left alone.
+pub fn make_type_parameters_generic(
+    input_item: TokenStream,
+    decls: &[TypeDeclaration],
+) -> Result<TokenStream, syn::Error> {
+    if decls.is_empty() {
+        return Ok(input_item);
+    }
+
+    let mut item: syn::ItemMod = syn::parse2(input_item)?;
+    let mut collector = Collector::default();
+    collector.visit_item_mod_mut(&mut item.clone());
+    let resolver = Resolver {
+        decls,
+        scopes: &collector.scopes,
+    };
+    let params = find_params(&collector.records, &resolver);
+    Genericizer {
+        decls,
+        resolver,
+        records: &collector.records,
+        params,
+        next_record: 0,
+        next_scope: 0,
+        scope_stack: vec![],
+        is_in_trait_or_trait_impl: false,
+        impl_params: vec![],
+    }
+    .visit_item_mod_mut(&mut item);
+
+    Ok(item.into_token_stream())
+}
+
+/// A module or a block in the template.
+#[derive(Default)]
+struct Scope {
+    parent: Option<usize>,
+    is_mod: bool,
+    /// Items other than modules and `use` declarations, by their names, with
+    /// their records if they have any.
+    items: HashMap<String, Option<usize>>,
+    /// Inline modules, by their names.
+    mods: HashMap<String, usize>,
+    /// Paths imported by `use` declarations, by the names they are imported as.
+    uses: HashMap<String, Vec<String>>,
+    /// Paths of the modules imported by glob imports.
+    globs: Vec<Vec<String>>,
+}
+
+impl Scope {
+    fn defines(&self, name: &str) -> bool {
+        self.items.contains_key(name)
+            || self.mods.contains_key(name)
+            || self.uses.contains_key(name)
+    }
+}
+
+/// An item that can be made generic over declared types, or the header of an
+/// `impl` block.
+struct Record {
+    /// The number of its own type and const parameters.
+    own_param_count: usize,
+    /// The paths mentioned in it, with the scopes they are mentioned in.
+    mentions: Vec<(usize, Vec<String>)>,
+}
+
+/// Collects the scopes and the records of the template, in the order they are
+/// visited, which `Genericizer` follows as well.
+#[derive(Default)]
+struct Collector {
+    scopes: Vec<Scope>,
+    scope_stack: Vec<usize>,
+    records: Vec<Record>,
+    open_records: Vec<usize>,
+}
+
+impl Collector {
+    fn enter_scope(&mut self, is_mod: bool) -> usize {
+        let index = self.scopes.len();
+        self.scopes.push(Scope {
+            parent: self.scope_stack.last().copied(),
+            is_mod,
+            ..Default::default()
+        });
+        self.scope_stack.push(index);
+        index
+    }
+
+    fn current_scope(&mut self) -> Option<&mut Scope> {
+        let index = *self.scope_stack.last()?;
+        Some(&mut self.scopes[index])
+    }
+
+    fn open_record(&mut self, name: Option<&syn::Ident>, generics: &syn::Generics) {
+        let index = self.records.len();
+        self.records.push(Record {
+            own_param_count: generics.type_params().count() + generics.const_params().count(),
+            mentions: vec![],
+        });
+        self.open_records.push(index);
+        if let (Some(name), Some(scope)) = (name, self.current_scope()) {
+            scope.items.insert(name.to_string(), Some(index));
+        }
+    }
+
+    fn mention(&mut self, segments: Vec<String>) {
+        let Some(&scope) = self.scope_stack.last() else {
+            return;
+        };
+        for &index in &self.open_records {
+            self.records[index].mentions.push((scope, segments.clone()));
+        }
+    }
+
+    fn mention_idents(&mut self, tokens: TokenStream) {
+        for tt in tokens {
+            match tt {
+                TokenTree::Ident(ident) => self.mention(vec![ident.to_string()]),
+                TokenTree::Group(group) => self.mention_idents(group.stream()),
+                _ => {}
+            }
+        }
+    }
+
+    fn add_use_tree(&mut self, prefix: &mut Vec<String>, tree: &syn::UseTree) {
+        let Some(scope) = self.current_scope() else {
+            return;
+        };
+        match tree {
+            syn::UseTree::Path(path) => {
+                prefix.push(path.ident.to_string());
+                self.add_use_tree(prefix, &path.tree);
+                prefix.pop();
+            }
+            syn::UseTree::Name(name) => {
+                let mut path = prefix.clone();
+                if name.ident != "self" {
+                    path.push(name.ident.to_string());
+                }
+                if let Some(last) = path.last() {
+                    scope.uses.insert(last.clone(), path);
+                }
+            }
+            syn::UseTree::Rename(rename) => {
+                let mut path = prefix.clone();
+                if rename.ident != "self" {
+                    path.push(rename.ident.to_string());
+                }
+                scope.uses.insert(rename.rename.to_string(), path);
+            }
+            syn::UseTree::Glob(_) => scope.globs.push(prefix.clone()),
+            syn::UseTree::Group(group) => {
+                for tree in &group.items {
+                    self.add_use_tree(prefix, tree);
+                }
+            }
+        }
+    }
+}
+
+impl VisitMut for Collector {
+    fn visit_item_mod_mut(&mut self, i: &mut syn::ItemMod) {
+        if i.content.is_none() {
+            if let Some(scope) = self.current_scope() {
+                scope.items.insert(i.ident.to_string(), None);
+            }
+            return;
+        }
+        let parent = self.scope_stack.last().copied();
+        let index = self.enter_scope(true);
+        if let Some(parent) = parent {
+            self.scopes[parent].mods.insert(i.ident.to_string(), index);
+        }
+        visit_mut::visit_item_mod_mut(self, i);
+        self.scope_stack.pop();
+    }
+
+    fn visit_block_mut(&mut self, i: &mut syn::Block) {
+        self.enter_scope(false);
+        visit_mut::visit_block_mut(self, i);
+        self.scope_stack.pop();
+    }
+
+    fn visit_item_use_mut(&mut self, i: &mut syn::ItemUse) {
+        let mut prefix = vec![];
+        if i.leading_colon.is_some() {
+            prefix.push("::".to_string());
+        }
+        self.add_use_tree(&mut prefix, &i.tree);
+    }
+
+    fn visit_item_const_mut(&mut self, i: &mut syn::ItemConst) {
+        if let Some(scope) = self.current_scope() {
+            scope.items.insert(i.ident.to_string(), None);
+        }
+        visit_mut::visit_item_const_mut(self, i);
+    }
+
+    fn visit_item_static_mut(&mut self, i: &mut syn::ItemStatic) {
+        if let Some(scope) = self.current_scope() {
+            scope.items.insert(i.ident.to_string(), None);
+        }
+        visit_mut::visit_item_static_mut(self, i);
+    }
+
+    fn visit_item_struct_mut(&mut self, i: &mut syn::ItemStruct) {
+        self.open_record(Some(&i.ident), &i.generics);
+        visit_mut::visit_item_struct_mut(self, i);
+        self.open_records.pop();
+    }
+
+    fn visit_item_enum_mut(&mut self, i: &mut syn::ItemEnum) {
+        self.open_record(Some(&i.ident), &i.generics);
+        visit_mut::visit_item_enum_mut(self, i);
+        self.open_records.pop();
+    }
+
+    fn visit_item_union_mut(&mut self, i: &mut syn::ItemUnion) {
+        self.open_record(Some(&i.ident), &i.generics);
+        visit_mut::visit_item_union_mut(self, i);
+        self.open_records.pop();
+    }
+
+    fn visit_item_type_mut(&mut self, i: &mut syn::ItemType) {
+        self.open_record(Some(&i.ident), &i.generics);
+        visit_mut::visit_item_type_mut(self, i);
+        self.open_records.pop();
+    }
+
+    fn visit_item_trait_mut(&mut self, i: &mut syn::ItemTrait) {
+        self.open_record(Some(&i.ident), &i.generics);
+        visit_mut::visit_item_trait_mut(self, i);
+        self.open_records.pop();
+    }
+
+    fn visit_item_fn_mut(&mut self, i: &mut syn::ItemFn) {
+        self.open_record(Some(&i.sig.ident), &i.sig.generics);
+        visit_mut::visit_item_fn_mut(self, i);
+        self.open_records.pop();
+    }
+
+    fn visit_item_impl_mut(&mut self, i: &mut syn::ItemImpl) {
+        self.open_record(None, &i.generics);
+        visit_impl_header_mut(self, i);
+        self.open_records.pop();
+        for item in &mut i.items {
+            self.visit_impl_item_mut(item);
+        }
+    }
+
+    fn visit_impl_item_fn_mut(&mut self, i: &mut syn::ImplItemFn) {
+        self.open_record(None, &i.sig.generics);
+        visit_mut::visit_impl_item_fn_mut(self, i);
+        self.open_records.pop();
+    }
+
+    fn visit_path_mut(&mut self, i: &mut syn::Path) {
+        if i.leading_colon.is_none() {
+            let segments = i.segments.iter().map(|s| s.ident.to_string()).collect();
+            self.mention(segments);
+        }
+        visit_mut::visit_path_mut(self, i);
+    }
+
+    fn visit_macro_mut(&mut self, i: &mut syn::Macro) {
+        match parse_macro_args(i) {
+            Some(mut args) => args.iter_mut().for_each(|arg| self.visit_expr_mut(arg)),
+            // NOTE: otherwise, every identifier in the tokens counts as a
+            // mention.
+            None => self.mention_idents(i.tokens.clone()),
+        }
+        visit_mut::visit_macro_mut(self, i);
+    }
+
+    fn visit_meta_list_mut(&mut self, i: &mut syn::MetaList) {
+        // NOTE: likewise, e.g. the arguments of `construct`.
+        self.mention_idents(i.tokens.clone());
+        visit_mut::visit_meta_list_mut(self, i);
+    }
+}
+
+/// The arguments of a macro call, if they are expressions, e.g. the ones of
+/// `assert!` and `println!`.
+fn parse_macro_args(i: &syn::Macro) -> Option<Punctuated<syn::Expr, syn::Token![,]>> {
+    i.parse_body_with(Punctuated::parse_terminated).ok()
+}
+
+/// Visits the parts of an `impl` block before its items, in the same order as
+/// `visit_mut::visit_item_impl_mut`.
+fn visit_impl_header_mut<V: VisitMut>(v: &mut V, i: &mut syn::ItemImpl) {
+    for attr in &mut i.attrs {
+        v.visit_attribute_mut(attr);
+    }
+    v.visit_generics_mut(&mut i.generics);
+    if let Some((_, path, _)) = &mut i.trait_ {
+        v.visit_path_mut(path);
+    }
+    v.visit_type_mut(&mut i.self_ty);
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Resolution {
+    Record(usize),
+    DeclaredType(usize),
+    Mod(usize),
+    Other,
+}
+
+/// Guards against cycles of `use` declarations.
+const MAX_RESOLUTION_DEPTH: usize = 8;
+
+struct Resolver<'a> {
+    decls: &'a [TypeDeclaration],
+    scopes: &'a [Scope],
+}
+
+impl Resolver<'_> {
+    /// Resolves the leading segments of the path, up to the first one that is
+    /// not a module, and returns the number of those segments along with what
+    /// they resolve to, if it's a record or a declared type.
+    fn resolve_leading(&self, scope: usize, segments: &[String]) -> Option<(usize, Resolution)> {
+        for len in 1..=segments.len() {
+            match self.resolve_path(scope, &segments[..len], 0) {
+                Resolution::Mod(_) => continue,
+                Resolution::Other => return None,
+                resolution => return Some((len, resolution)),
+            }
+        }
+        None
+    }
+
+    fn resolve_path(&self, scope: usize, segments: &[String], depth: usize) -> Resolution {
+        let Some((first, rest)) = segments.split_first() else {
+            return Resolution::Other;
+        };
+        if depth > MAX_RESOLUTION_DEPTH {
+            return Resolution::Other;
+        }
+
+        let mut module = match first.as_str() {
+            "self" => self.enclosing_mod(scope),
+            "super" => match self.parent_mod(self.enclosing_mod(scope)) {
+                Some(module) => module,
+                None => return Resolution::Other,
+            },
+            "crate" | "::" | "Self" => return Resolution::Other,
+            _ => match self.resolve_name(scope, first, rest.is_empty(), depth) {
+                resolution if rest.is_empty() => return resolution,
+                Resolution::Mod(module) => module,
+                _ => return Resolution::Other,
+            },
+        };
+        for (i, segment) in rest.iter().enumerate() {
+            let is_last = i + 1 == rest.len();
+            let resolution = if segment == "super" {
+                match self.parent_mod(module) {
+                    Some(module) => Resolution::Mod(module),
+                    None => Resolution::Other,
+                }
+            } else {
+                self.resolve_in_mod(module, segment, is_last, depth)
+            };
+            match resolution {
+                resolution if is_last => return resolution,
+                Resolution::Mod(next) => module = next,
+                _ => return Resolution::Other,
+            }
+        }
+        Resolution::Mod(module)
+    }
+
+    /// Resolves a name from the scope, looking into the enclosing blocks up to
+    /// the enclosing module.
+    fn resolve_name(&self, scope: usize, name: &str, is_last: bool, depth: usize) -> Resolution {
+        let mut scope = scope;
+        loop {
+            if let Some(resolution) = self.resolve_local(scope, name, is_last, depth) {
+                return resolution;
+            }
+            if self.scopes[scope].is_mod {
+                return self.resolve_imported(scope, name, is_last, depth);
+            }
+            scope = self.scopes[scope]
+                .parent
+                .expect("blocks should be in modules");
+        }
+    }
+
+    fn resolve_in_mod(&self, module: usize, name: &str, is_last: bool, depth: usize) -> Resolution {
+        self.resolve_local(module, name, is_last, depth)
+            .unwrap_or_else(|| self.resolve_imported(module, name, is_last, depth))
+    }
+
+    /// Resolves a name defined or imported by name in the scope.
+    fn resolve_local(
+        &self,
+        scope: usize,
+        name: &str,
+        is_last: bool,
+        depth: usize,
+    ) -> Option<Resolution> {
+        let the_scope = &self.scopes[scope];
+        let item = (the_scope.items.get(name))
+            .map(|record| record.map_or(Resolution::Other, Resolution::Record));
+        let module = the_scope
+            .mods
+            .get(name)
+            .map(|&module| Resolution::Mod(module));
+        // NOTE: an item and a module can share a name, since they are in
+        // different namespaces.
+        let resolution = if is_last {
+            item.or(module)
+        } else {
+            module.or(item)
+        };
+        if resolution.is_some() {
+            return resolution;
+        }
+
+        let path = the_scope.uses.get(name)?;
+        Some(self.resolve_path(scope, path, depth + 1))
+    }
+
+    /// Resolves a name to a declared type, or through the glob imports of the
+    /// module.
+    fn resolve_imported(
+        &self,
+        module: usize,
+        name: &str,
+        is_last: bool,
+        depth: usize,
+    ) -> Resolution {
+        if let Some(index) = (self.decls.iter()).position(|decl| decl.target_name_ident() == name) {
+            return Resolution::DeclaredType(index);
+        }
+        for glob in &self.scopes[module].globs {
+            let Resolution::Mod(imported) = self.resolve_path(module, glob, depth + 1) else {
+                continue;
+            };
+            let resolution = self.resolve_in_mod(imported, name, is_last, depth + 1);
+            if resolution != Resolution::Other {
+                return resolution;
+            }
+        }
+        Resolution::Other
+    }
+
+    fn enclosing_mod(&self, scope: usize) -> usize {
+        let mut scope = scope;
+        while !self.scopes[scope].is_mod {
+            scope = self.scopes[scope]
+                .parent
+                .expect("blocks should be in modules");
+        }
+        scope
+    }
+
+    fn parent_mod(&self, module: usize) -> Option<usize> {
+        Some(self.enclosing_mod(self.scopes[module].parent?))
+    }
+}
+
+/// Finds the declared types that each record should be made generic over, by
+/// their indices in the `types` block.
+fn find_params(records: &[Record], resolver: &Resolver) -> Vec<Vec<usize>> {
+    let resolutions: Vec<Vec<Resolution>> = records
+        .iter()
+        .map(|record| {
+            (record.mentions.iter())
+                .filter_map(|(scope, segments)| resolver.resolve_leading(*scope, segments))
+                .map(|(_, resolution)| resolution)
+                .collect()
+        })
+        .collect();
+
+    let mut params: Vec<Vec<usize>> = vec![vec![]; records.len()];
+    // NOTE: an item mentioning another one carries the declared types of the
+    // latter as well, so it's repeated until nothing changes.
+    loop {
+        let mut has_changed = false;
+        for (i, resolutions) in resolutions.iter().enumerate() {
+            let mut new_params: Vec<usize> = vec![];
+            for resolution in resolutions {
+                match resolution {
+                    Resolution::DeclaredType(index) => new_params.push(*index),
+                    Resolution::Record(index) => new_params.extend(&params[*index]),
+                    _ => {}
+                }
+            }
+            new_params.sort();
+            new_params.dedup();
+            if new_params != params[i] {
+                params[i] = new_params;
+                has_changed = true;
+            }
+        }
+        if !has_changed {
+            break;
+        }
+    }
+
+    params
+}
+
+struct Genericizer<'a> {
+    decls: &'a [TypeDeclaration],
+    resolver: Resolver<'a>,
+    records: &'a [Record],
+    params: Vec<Vec<usize>>,
+    next_record: usize,
+    next_scope: usize,
+    scope_stack: Vec<usize>,
+    is_in_trait_or_trait_impl: bool,
+    /// The declared types that the enclosing `impl` block is generic over.
+    impl_params: Vec<usize>,
+}
+
+impl Genericizer<'_> {
+    fn take_record(&mut self) -> Vec<usize> {
+        let index = self.next_record;
+        self.next_record += 1;
+        self.params[index].clone()
+    }
+
+    fn enter_scope(&mut self) -> usize {
+        let index = self.next_scope;
+        self.next_scope += 1;
+        self.scope_stack.push(index);
+        index
+    }
+
+    fn add_to_generics(&self, generics: &mut syn::Generics, params: &[usize], with_bounds: bool) {
+        for &i in params {
+            let decl = &self.decls[i];
+            let target_name_ident = decl.target_name_ident();
+            generics.params.push(syn::parse_quote!(#target_name_ident));
+            // NOTE: bounds are put into the where clause, so that the standalone
+            // attributes (which look for the first parenthesized group after
+            // `fn`) won't be confused by something like `Fn(…)`.
+            let bounds = decl.bounds();
+            if with_bounds && !bounds.is_empty() {
+                generics
+                    .make_where_clause()
+                    .predicates
+                    .push(syn::parse_quote!(#target_name_ident: #bounds));
+            }
+        }
+    }
+
+    fn add_fn_params_to_generics(&self, generics: &mut syn::Generics, params: Vec<usize>) -> bool {
+        let params: Vec<usize> = params
+            .into_iter()
+            .filter(|i| !self.impl_params.contains(i))
+            .collect();
+        self.add_to_generics(generics, &params, true);
+        !params.is_empty()
+    }
+}
+
+impl VisitMut for Genericizer<'_> {
+    fn visit_item_mod_mut(&mut self, i: &mut syn::ItemMod) {
+        if i.content.is_none() {
+            return;
+        }
+        let index = self.enter_scope();
+        visit_mut::visit_item_mod_mut(self, i);
+        self.scope_stack.pop();
+
+        let Some((_, items)) = &mut i.content else {
+            return;
+        };
+        for decl in self.decls.iter().rev() {
+            let target_name_ident = decl.target_name_ident();
+            if self.resolver.scopes[index].defines(&target_name_ident.to_string()) {
+                continue;
+            }
+            items.insert(
+                0,
+                syn::parse_quote!(#[allow(dead_code)] enum #target_name_ident {}),
+            );
+        }
+    }
+
+    fn visit_block_mut(&mut self, i: &mut syn::Block) {
+        self.enter_scope();
+        visit_mut::visit_block_mut(self, i);
+        self.scope_stack.pop();
+    }
+
+    fn visit_item_struct_mut(&mut self, i: &mut syn::ItemStruct) {
+        let params = self.take_record();
+        self.add_to_generics(&mut i.generics, &params, true);
+        visit_mut::visit_item_struct_mut(self, i);
+    }
+
+    fn visit_item_enum_mut(&mut self, i: &mut syn::ItemEnum) {
+        let params = self.take_record();
+        self.add_to_generics(&mut i.generics, &params, true);
+        visit_mut::visit_item_enum_mut(self, i);
+    }
+
+    fn visit_item_union_mut(&mut self, i: &mut syn::ItemUnion) {
+        let params = self.take_record();
+        self.add_to_generics(&mut i.generics, &params, true);
+        visit_mut::visit_item_union_mut(self, i);
+    }
+
+    fn visit_item_type_mut(&mut self, i: &mut syn::ItemType) {
+        let params = self.take_record();
+        // NOTE: bounds on type aliases are not enforced, and linted against.
+        self.add_to_generics(&mut i.generics, &params, false);
+        visit_mut::visit_item_type_mut(self, i);
+    }
+
+    fn visit_path_mut(&mut self, i: &mut syn::Path) {
+        let scope = *self.scope_stack.last().expect("paths should be in scopes");
+        let segments: Vec<String> = i.segments.iter().map(|s| s.ident.to_string()).collect();
+        let leading = match i.leading_colon {
+            Some(_) => None,
+            None => self.resolver.resolve_leading(scope, &segments),
+        };
+        if let Some((len, Resolution::Record(index))) = leading {
+            let args = self.params[index]
+                .iter()
+                .map(|&i| self.decls[i].target_name_ident());
+            let segment = &mut i.segments[len - 1];
+            match &mut segment.arguments {
+                syn::PathArguments::None if !self.params[index].is_empty() => {
+                    // NOTE: the item's own parameters are left to inference,
+                    // and with `::`, so that it works in expressions too.
+                    let inferred =
+                        (0..self.records[index].own_param_count).map(|_| quote::quote!(_));
+                    segment.arguments = syn::PathArguments::AngleBracketed(syn::parse_quote!(
+                        ::<#(#inferred,)* #(#args),*>
+                    ));
+                }
+                syn::PathArguments::AngleBracketed(existing) => {
+                    existing.args.extend(
+                        args.map(|arg| -> syn::GenericArgument { syn::parse_quote!(#arg) }),
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        visit_mut::visit_path_mut(self, i);
+    }
+
+    fn visit_macro_mut(&mut self, i: &mut syn::Macro) {
+        if let Some(mut args) = parse_macro_args(i) {
+            args.iter_mut().for_each(|arg| self.visit_expr_mut(arg));
+            i.tokens = args.into_token_stream();
+        }
+        visit_mut::visit_macro_mut(self, i);
+    }
+
+    fn visit_item_fn_mut(&mut self, i: &mut syn::ItemFn) {
+        let impl_params = std::mem::take(&mut self.impl_params);
+        let params = self.take_record();
+        if self.add_fn_params_to_generics(&mut i.sig.generics, params) {
+            // NOTE: tests can't be generic. They are still type-checked.
+            let len_before = i.attrs.len();
+            i.attrs.retain(|attr| {
+                (attr.path().segments.last()).is_none_or(|segment| {
+                    !(segment.ident == "test"
+                        || segment.ident == "should_panic"
+                        || segment.ident == "ignore")
+                })
+            });
+            if i.attrs.len() != len_before {
+                i.attrs.push(syn::parse_quote!(#[allow(dead_code)]));
+            }
+        }
+
+        let is_in_trait_or_trait_impl = self.is_in_trait_or_trait_impl;
+        self.is_in_trait_or_trait_impl = false;
+        visit_mut::visit_item_fn_mut(self, i);
+        self.is_in_trait_or_trait_impl = is_in_trait_or_trait_impl;
+        self.impl_params = impl_params;
+    }
+
+    fn visit_item_impl_mut(&mut self, i: &mut syn::ItemImpl) {
+        let params = self.take_record();
+        self.add_to_generics(&mut i.generics, &params, true);
+        visit_impl_header_mut(self, i);
+
+        let impl_params = std::mem::replace(&mut self.impl_params, params);
+        let is_in_trait_or_trait_impl = self.is_in_trait_or_trait_impl;
+        self.is_in_trait_or_trait_impl = i.trait_.is_some();
+        for item in &mut i.items {
+            self.visit_impl_item_mut(item);
+        }
+        self.is_in_trait_or_trait_impl = is_in_trait_or_trait_impl;
+        self.impl_params = impl_params;
+    }
+
+    fn visit_impl_item_fn_mut(&mut self, i: &mut syn::ImplItemFn) {
+        let params = self.take_record();
+        // NOTE: adding generic parameters to methods in trait implementations
+        // would make them mismatch the trait.
+        if !self.is_in_trait_or_trait_impl {
+            self.add_fn_params_to_generics(&mut i.sig.generics, params);
+        }
+
+        let is_in_trait_or_trait_impl = self.is_in_trait_or_trait_impl;
+        self.is_in_trait_or_trait_impl = false;
+        visit_mut::visit_impl_item_fn_mut(self, i);
+        self.is_in_trait_or_trait_impl = is_in_trait_or_trait_impl;
+    }
+
+    fn visit_item_trait_mut(&mut self, i: &mut syn::ItemTrait) {
+        let params = self.take_record();
+        self.add_to_generics(&mut i.generics, &params, true);
+
+        let impl_params = std::mem::take(&mut self.impl_params);
+        let is_in_trait_or_trait_impl = self.is_in_trait_or_trait_impl;
+        self.is_in_trait_or_trait_impl = true;
+        visit_mut::visit_item_trait_mut(self, i);
+        self.is_in_trait_or_trait_impl = is_in_trait_or_trait_impl;
+        self.impl_params = impl_params;
+    }
+}
+
+pub fn mentions(tokens: TokenStream, target_name_ident: &syn::Ident) -> bool {
+    tokens.into_iter().any(|tt| match tt {
+        TokenTree::Ident(ident) => ident == *target_name_ident,
+        TokenTree::Group(group) => mentions(group.stream(), target_name_ident),
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::make_type_parameters_generic;
+    use crate::attributes::define::AttributeOptions;
+
+    #[test]
+    fn basic() {
+        let opts: AttributeOptions =
+            syn::parse2(quote::quote!(macro_rules! the_macro_name; types(Foo: Trait, Bar)))
+                .unwrap();
+        let input_item = quote::quote! {
+            mod __ {
+                struct AStruct(Bar);
+
+                fn an_fn(foo: Foo) {}
+
+                fn a_second_fn<T>() {
+                    let _: Option<Bar> = None;
+                }
+
+                impl AStruct {
+                    fn a_method(&self, foo: Foo) {}
+                }
+
+                impl ATrait for AStruct {
+                    fn a_trait_method(&self, foo: Foo) {}
+                }
+
+                mod a_sub_mod {}
+            }
+        };
+
+        let expected = quote::quote! {
+            mod __ {
+                #[allow(dead_code)]
+                enum Foo {}
+                #[allow(dead_code)]
+                enum Bar {}
+
+                struct AStruct<Bar>(Bar);
+
+                fn an_fn<Foo>(foo: Foo) where Foo: Trait {}
+
+                fn a_second_fn<T, Bar>() {
+                    let _: Option<Bar> = None;
+                }
+
+                impl<Bar> AStruct::<Bar> {
+                    fn a_method<Foo>(&self, foo: Foo) where Foo: Trait {}
+                }
+
+                impl<Bar> ATrait for AStruct::<Bar> {
+                    fn a_trait_method(&self, foo: Foo) {}
+                }
+
+                mod a_sub_mod {
+                    #[allow(dead_code)]
+                    enum Foo {}
+                    #[allow(dead_code)]
+                    enum Bar {}
+                }
+            }
+        };
+
+        let actual = make_type_parameters_generic(input_item, opts.types()).unwrap();
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn carriers() {
+        let opts: AttributeOptions =
+            syn::parse2(quote::quote!(macro_rules! the_macro_name; types(Foo: Trait))).unwrap();
+        let input_item = quote::quote! {
+            mod __ {
+                struct Wrapper {
+                    foo: Foo,
+                }
+
+                struct Outer(Wrapper);
+
+                type Alias = Vec<Foo>;
+
+                trait Get {
+                    fn get(&self) -> &Foo;
+                }
+
+                impl Get for Wrapper {
+                    fn get(&self) -> &Foo {
+                        &self.foo
+                    }
+                }
+
+                fn wrap(foo: Foo) -> Outer {
+                    Outer(Wrapper { foo })
+                }
+
+                const NONE: Option<Foo> = None;
+            }
+        };
+
+        let expected = quote::quote! {
+            mod __ {
+                #[allow(dead_code)]
+                enum Foo {}
+
+                struct Wrapper<Foo> where Foo: Trait {
+                    foo: Foo,
+                }
+
+                struct Outer<Foo>(Wrapper::<Foo>) where Foo: Trait;
+
+                type Alias<Foo> = Vec<Foo>;
+
+                trait Get<Foo> where Foo: Trait {
+                    fn get(&self) -> &Foo;
+                }
+
+                impl<Foo> Get::<Foo> for Wrapper::<Foo> where Foo: Trait {
+                    fn get(&self) -> &Foo {
+                        &self.foo
+                    }
+                }
+
+                fn wrap<Foo>(foo: Foo) -> Outer::<Foo> where Foo: Trait {
+                    Outer::<Foo>(Wrapper::<Foo> { foo })
+                }
+
+                const NONE: Option<Foo> = None;
+            }
+        };
+
+        let actual = make_type_parameters_generic(input_item, opts.types()).unwrap();
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn function_carriers() {
+        let opts: AttributeOptions =
+            syn::parse2(quote::quote!(macro_rules! the_macro_name; types(Foo: Trait))).unwrap();
+        let input_item = quote::quote! {
+            mod __ {
+                fn make() -> Foo {
+                    Foo::new()
+                }
+
+                fn make_with<T: Into<u8>>(t: T) -> (T, Foo) {
+                    (t, make())
+                }
+
+                #[test]
+                fn a_test() {
+                    assert!(make().is_ok());
+                    let _ = make_with(1u8);
+                    let _ = make_with::<u8>(1);
+                }
+
+                fn another_fn() {
+                    fn make() -> u8 {
+                        0
+                    }
+                    make();
+                }
+            }
+        };
+
+        let expected = quote::quote! {
+            mod __ {
+                #[allow(dead_code)]
+                enum Foo {}
+
+                fn make<Foo>() -> Foo where Foo: Trait {
+                    Foo::new()
+                }
+
+                fn make_with<T: Into<u8>, Foo>(t: T) -> (T, Foo) where Foo: Trait {
+                    (t, make::<Foo>())
+                }
+
+                #[allow(dead_code)]
+                fn a_test<Foo>() where Foo: Trait {
+                    assert!(make::<Foo>().is_ok());
+                    let _ = make_with::<_, Foo>(1u8);
+                    let _ = make_with::<u8, Foo>(1);
+                }
+
+                fn another_fn() {
+                    fn make() -> u8 {
+                        0
+                    }
+                    make();
+                }
+            }
+        };
+
+        let actual = make_type_parameters_generic(input_item, opts.types()).unwrap();
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn scopes() {
+        let opts: AttributeOptions =
+            syn::parse2(quote::quote!(macro_rules! the_macro_name; types(Foo: Trait))).unwrap();
+        let input_item = quote::quote! {
+            mod __ {
+                struct Wrapper(Foo);
+
+                mod globbed {
+                    use super::*;
+
+                    fn wrap() -> Wrapper {
+                        todo!()
+                    }
+                }
+
+                mod imported {
+                    use super::Wrapper as Renamed;
+
+                    fn wrap() -> Renamed {
+                        super::Wrapper(todo!())
+                    }
+                }
+
+                mod unrelated {
+                    struct Wrapper(u8);
+
+                    fn wrap() -> Wrapper {
+                        self::Wrapper(0)
+                    }
+                }
+
+                mod shadowed {
+                    struct Foo;
+
+                    fn make() -> Foo {
+                        Foo
+                    }
+                }
+            }
+        };
+
+        let expected = quote::quote! {
+            mod __ {
+                #[allow(dead_code)]
+                enum Foo {}
+
+                struct Wrapper<Foo>(Foo) where Foo: Trait;
+
+                mod globbed {
+                    #[allow(dead_code)]
+                    enum Foo {}
+
+                    use super::*;
+
+                    fn wrap<Foo>() -> Wrapper::<Foo> where Foo: Trait {
+                        todo!()
+                    }
+                }
+
+                mod imported {
+                    #[allow(dead_code)]
+                    enum Foo {}
+
+                    use super::Wrapper as Renamed;
+
+                    fn wrap<Foo>() -> Renamed::<Foo> where Foo: Trait {
+                        super::Wrapper::<Foo>(todo!())
+                    }
+                }
+
+                mod unrelated {
+                    #[allow(dead_code)]
+                    enum Foo {}
+
+                    struct Wrapper(u8);
+
+                    fn wrap() -> Wrapper {
+                        self::Wrapper(0)
+                    }
+                }
+
+                mod shadowed {
+                    struct Foo;
+
+                    fn make() -> Foo {
+                        Foo
+                    }
+                }
+            }
+        };
+
+        let actual = make_type_parameters_generic(input_item, opts.types()).unwrap();
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+}
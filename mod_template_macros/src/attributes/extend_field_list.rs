@@ -0,0 +1,197 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{parse::Parser, punctuated::Punctuated};
+
+pub fn extend_field_list(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let opts: AttributeOptions = match syn::parse2(attr) {
+        Ok(attr) => attr,
+        Err(err) => return err.to_compile_error(),
+    };
+    let Ok(mut item) = syn::parse2::<syn::ItemStruct>(item) else {
+        proc_macro_error::abort_call_site!("this attribute can only be applied to a `struct`");
+    };
+
+    let field_list = TokenStream::from_iter(opts.field_list);
+    let result = match item.fields {
+        syn::Fields::Named(ref mut fields) => do_extend_field_list(
+            &mut fields.named,
+            opts.direction,
+            field_list,
+            syn::Field::parse_named,
+        ),
+        syn::Fields::Unnamed(ref mut fields) => do_extend_field_list(
+            &mut fields.unnamed,
+            opts.direction,
+            field_list,
+            syn::Field::parse_unnamed,
+        ),
+        syn::Fields::Unit => {
+            proc_macro_error::abort_call_site!("a unit struct has no field list to extend")
+        }
+    };
+    if let Err(err) = result {
+        return err.to_compile_error();
+    }
+
+    item.into_token_stream()
+}
+
+fn do_extend_field_list(
+    fields: &mut Punctuated<syn::Field, syn::Token![,]>,
+    direction: Direction,
+    field_list: TokenStream,
+    parse_field: fn(syn::parse::ParseStream) -> syn::Result<syn::Field>,
+) -> syn::Result<()> {
+    let parser = |input: syn::parse::ParseStream| {
+        Punctuated::<syn::Field, syn::Token![,]>::parse_terminated_with(input, parse_field)
+    };
+    let new_fields = parser.parse2(field_list)?;
+    match direction {
+        Direction::Append => fields.extend(new_fields),
+    }
+    Ok(())
+}
+
+pub struct AttributeOptions {
+    direction: Direction,
+    field_list: Vec<TokenTree>,
+}
+
+impl AttributeOptions {
+    /// Parses a bare field list, which is appended, as in the blocks of
+    /// attribute substitutions in macro calls (e.g. `{ extra: Foo }`).
+    pub fn parse_field_list(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let field_list: TokenStream = input.parse()?;
+
+        Ok(AttributeOptions {
+            direction: Direction::Append,
+            field_list: field_list.into_iter().collect(),
+        })
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    pub fn field_list(&self) -> &Vec<TokenTree> {
+        &self.field_list
+    }
+
+    pub fn is_noop(&self) -> bool {
+        self.field_list.is_empty()
+    }
+}
+
+/// Only `append` is supported for now
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(test, derive(Debug))]
+pub enum Direction {
+    Append,
+}
+
+impl syn::parse::Parse for AttributeOptions {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let _: syn::Token![..] = input.parse()?;
+        if input.is_empty() {
+            return Ok(AttributeOptions {
+                direction: Direction::Append,
+                field_list: vec![],
+            });
+        }
+        let _: syn::Token![,] = input.parse()?;
+
+        Self::parse_field_list(input)
+    }
+}
+
+impl quote::ToTokens for AttributeOptions {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self.direction() {
+            Direction::Append => quote::quote!(..,).to_tokens(tokens),
+        }
+        let list = self.field_list().clone();
+        TokenStream::from_iter(list).to_tokens(tokens);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use proc_macro2::TokenStream;
+
+    use super::{extend_field_list, AttributeOptions, Direction};
+
+    #[test]
+    fn named() {
+        let input_attr = quote::quote!(.., pub extra: Foo, #[allow(dead_code)] other: u8);
+        let input_item = quote::quote! {
+            #[derive(Debug)]
+            struct Bar<T> where T: Clone {
+                value: T
+            }
+        };
+
+        let expected = quote::quote! {
+            #[derive(Debug)]
+            struct Bar<T> where T: Clone {
+                value: T,
+                pub extra: Foo,
+                #[allow(dead_code)] other: u8
+            }
+        };
+
+        let actual = extend_field_list(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn unnamed() {
+        let input_attr = quote::quote!(.., pub Foo,);
+        let input_item = quote::quote! {
+            struct Bar(u8,);
+        };
+
+        let expected = quote::quote! {
+            struct Bar(u8, pub Foo);
+        };
+
+        let actual = extend_field_list(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn as_is() {
+        let input_attr = quote::quote!(..);
+        let input_item = quote::quote! {
+            struct Empty {}
+        };
+
+        let expected = quote::quote! {
+            struct Empty {}
+        };
+
+        let actual = extend_field_list(input_attr, input_item);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[derive(Debug, PartialEq, Eq, typed_builder::TypedBuilder)]
+    pub struct AttributeOptionsForTest {
+        direction: Direction,
+        field_list: String,
+    }
+    impl From<AttributeOptions> for AttributeOptionsForTest {
+        fn from(value: AttributeOptions) -> Self {
+            let AttributeOptions {
+                direction,
+                field_list,
+            } = value;
+
+            Self {
+                direction,
+                field_list: TokenStream::from_iter(field_list).to_string(),
+            }
+        }
+    }
+}
@@ -1,9 +1,11 @@
 mod __monomorphize_mod;
 mod construct;
 mod define;
+mod extend_field_list;
 mod extend_parameter_list;
 
 pub use __monomorphize_mod::__monomorphize_mod;
 pub use construct::construct;
-pub use define::define;
+pub use define::{__compiler_check_dummy, define};
+pub use extend_field_list::extend_field_list;
 pub use extend_parameter_list::extend_parameter_list;
@@ -22,6 +22,16 @@ pub fn __monomorphize_mod(
     attributes::__monomorphize_mod(attr.into(), item.into()).into()
 }
 
+/// See [`mod_template::__compiler_check_dummy`](../mod_template/attr.__compiler_check_dummy.html).
+#[proc_macro_attribute]
+#[proc_macro_error::proc_macro_error]
+pub fn __compiler_check_dummy(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    attributes::__compiler_check_dummy(attr.into(), item.into()).into()
+}
+
 /// See [`mod_template::construct`](../mod_template/attr.construct.html).
 #[proc_macro_attribute]
 #[proc_macro_error::proc_macro_error]
@@ -41,3 +51,13 @@ pub fn extend_parameter_list(
 ) -> proc_macro::TokenStream {
     attributes::extend_parameter_list(attr.into(), item.into()).into()
 }
+
+/// See [`mod_template::extend_field_list`](../mod_template/attr.extend_field_list.html).
+#[proc_macro_attribute]
+#[proc_macro_error::proc_macro_error]
+pub fn extend_field_list(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    attributes::extend_field_list(attr.into(), item.into()).into()
+}
@@ -0,0 +1,167 @@
+use proc_macro2::{Delimiter, Group, TokenStream, TokenTree};
+use quote::{ToTokens, TokenStreamExt};
+
+use super::item_names::{item_name_ident, item_names};
+
+/// Puts `injection` at the beginning of the given module content (right after
+/// its inner attributes), as well as at the beginning of the content of every
+/// inline module nested in it. Injected items are left out of the modules that
+/// define or import items of the same names, which would collide with them.
+pub fn inject_into_mods(input: TokenStream, injection: &TokenStream) -> TokenStream {
+    let input: Vec<TokenTree> = input.into_iter().collect();
+    let mut output = TokenStream::new();
+
+    // inner attributes (`#![…]`) must stay at the top.
+    let mut rest = &input[..];
+    while let [TokenTree::Punct(hash), TokenTree::Punct(excl), group @ TokenTree::Group(..), ..] =
+        rest
+    {
+        if hash.as_char() != '#' || excl.as_char() != '!' {
+            break;
+        }
+        output.append(hash.clone());
+        output.append(excl.clone());
+        output.append(group.clone());
+        rest = &rest[3..];
+    }
+
+    output.extend(without_shadowed(injection, rest));
+    output.extend(inject_into_nested_mods(
+        rest.iter().cloned().collect(),
+        injection,
+    ));
+
+    output
+}
+
+fn without_shadowed(injection: &TokenStream, content: &[TokenTree]) -> TokenStream {
+    let Ok(content) = syn::parse2::<syn::File>(content.iter().cloned().collect()) else {
+        return injection.clone();
+    };
+    let Ok(mut injection) = syn::parse2::<syn::File>(injection.clone()) else {
+        return injection.clone();
+    };
+    let names = item_names(&content.items);
+    injection
+        .items
+        .retain(|item| item_name_ident(item).is_none_or(|name| !names.contains(name)));
+    injection.into_token_stream()
+}
+
+/// Like [`inject_into_mods`], but leaves the beginning of the given tokens
+/// untouched.
+pub fn inject_into_nested_mods(input: TokenStream, injection: &TokenStream) -> TokenStream {
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum State {
+        Other,
+        AfterModKeyword,
+        AfterModName,
+    }
+
+    let mut output = TokenStream::new();
+
+    let mut state = State::Other;
+    for tt in input {
+        let tt = match tt {
+            TokenTree::Group(group) => {
+                let stream =
+                    if state == State::AfterModName && group.delimiter() == Delimiter::Brace {
+                        inject_into_mods(group.stream(), injection)
+                    } else {
+                        inject_into_nested_mods(group.stream(), injection)
+                    };
+                let mut new_group = Group::new(group.delimiter(), stream);
+                new_group.set_span(group.span());
+                TokenTree::Group(new_group)
+            }
+            tt => tt,
+        };
+
+        state = match (&tt, state) {
+            (TokenTree::Ident(ident), _) if ident == "mod" => State::AfterModKeyword,
+            (TokenTree::Ident(_), State::AfterModKeyword) => State::AfterModName,
+            _ => State::Other,
+        };
+
+        output.append(tt);
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::inject_into_mods;
+
+    #[test]
+    fn basic() {
+        let injection = quote::quote!(
+            type Foo = Bar;
+        );
+        let input = quote::quote! {
+            #![allow(unused)]
+            fn an_fn() {
+                mod a_mod_in_fn {}
+            }
+            mod a_sub_mod {
+                #![allow(unused)]
+                mod a_sub_sub_mod {}
+            }
+            mod an_out_of_line_mod;
+        };
+
+        let expected = quote::quote! {
+            #![allow(unused)]
+            type Foo = Bar;
+            fn an_fn() {
+                mod a_mod_in_fn {
+                    type Foo = Bar;
+                }
+            }
+            mod a_sub_mod {
+                #![allow(unused)]
+                type Foo = Bar;
+                mod a_sub_sub_mod {
+                    type Foo = Bar;
+                }
+            }
+            mod an_out_of_line_mod;
+        };
+
+        let actual = inject_into_mods(input, &injection);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn shadowed() {
+        let injection = quote::quote!(
+            type Foo = Bar;
+            const BAZ: u8 = 0;
+        );
+        let input = quote::quote! {
+            struct Foo;
+            mod a_sub_mod {
+                use super::Foo as BAZ;
+            }
+            mod another_sub_mod {}
+        };
+
+        let expected = quote::quote! {
+            const BAZ: u8 = 0;
+            struct Foo;
+            mod a_sub_mod {
+                type Foo = Bar;
+                use super::Foo as BAZ;
+            }
+            mod another_sub_mod {
+                type Foo = Bar;
+                const BAZ: u8 = 0;
+            }
+        };
+
+        let actual = inject_into_mods(input, &injection);
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+}
@@ -0,0 +1,48 @@
+use proc_macro2::Ident;
+
+pub fn item_name_ident(item: &syn::Item) -> Option<&Ident> {
+    match item {
+        syn::Item::Const(item) => Some(&item.ident),
+        syn::Item::Enum(item) => Some(&item.ident),
+        syn::Item::ExternCrate(item) => Some(&item.ident),
+        syn::Item::Fn(item) => Some(&item.sig.ident),
+        syn::Item::Macro(item) => item.ident.as_ref(),
+        syn::Item::Mod(item) => Some(&item.ident),
+        syn::Item::Static(item) => Some(&item.ident),
+        syn::Item::Struct(item) => Some(&item.ident),
+        syn::Item::Trait(item) => Some(&item.ident),
+        syn::Item::TraitAlias(item) => Some(&item.ident),
+        syn::Item::Type(item) => Some(&item.ident),
+        syn::Item::Union(item) => Some(&item.ident),
+        _ => None,
+    }
+}
+
+/// The names of the items, including the names brought into scope by `use`
+/// declarations (except glob imports).
+pub fn item_names(items: &[syn::Item]) -> Vec<Ident> {
+    fn collect_use_names(tree: &syn::UseTree, names: &mut Vec<Ident>) {
+        match tree {
+            syn::UseTree::Path(path) => collect_use_names(&path.tree, names),
+            syn::UseTree::Name(name) if name.ident != "self" => names.push(name.ident.clone()),
+            syn::UseTree::Rename(rename) if rename.rename != "_" => {
+                names.push(rename.rename.clone())
+            }
+            syn::UseTree::Group(group) => {
+                for tree in &group.items {
+                    collect_use_names(tree, names);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut names: Vec<Ident> = vec![];
+    for item in items {
+        match item {
+            syn::Item::Use(item_use) => collect_use_names(&item_use.tree, &mut names),
+            item => names.extend(item_name_ident(item).cloned()),
+        }
+    }
+    names
+}
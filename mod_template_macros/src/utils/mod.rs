@@ -1,2 +1,5 @@
+pub mod inject_into_mods;
+pub mod item_names;
+pub mod nested_instances;
 pub mod substitute_attributes;
 mod token_stream_or_syn_error;
@@ -0,0 +1,254 @@
+use proc_macro2::{Delimiter, Group, Ident, TokenStream, TokenTree};
+
+use super::token_stream_or_syn_error::TokenStreamOrSynError;
+
+/// The path of the helper attribute marking macro calls in a template that
+/// instantiate other templates.
+pub const NESTED_MARKER: &str = "__NESTED";
+
+const FORWARD_KEYWORD: &str = "__FORWARD";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ForwardedKind {
+    Construction,
+    AttributeSubstitution,
+}
+
+/// Returns what replaces `__FORWARD(«target name»)`.
+pub type Forwarder<'a> = dyn Fn(ForwardedKind, &Ident) -> syn::Result<TokenStream> + 'a;
+
+/// Whether the content of an attribute (the part in brackets) is the marker of
+/// nested instances.
+pub fn is_nested_marker(attr_content: &TokenStream) -> bool {
+    let mut iter = attr_content.clone().into_iter();
+    matches!(
+        (iter.next(), iter.next()),
+        (Some(TokenTree::Ident(ident)), None) if ident == NESTED_MARKER
+    )
+}
+
+/// Removes the markers of nested instances, and replaces every
+/// `__FORWARD(«target name»)` in the `constructions` and
+/// `attribute_substitutions` blocks of the marked macro calls.
+///
+/// If `keeps_nested_instances` is `false`, the marked macro calls are replaced
+/// with placeholder items instead, after `__FORWARD`s in them are checked.
+pub fn forward_into_nested_instances(
+    input: TokenStream,
+    forwarder: &Forwarder,
+    keeps_nested_instances: bool,
+) -> Result<TokenStream, syn::Error> {
+    let mut input = input.into_iter().peekable();
+    let mut output = TokenStreamOrSynError::new();
+
+    while let Some(tt) = input.next() {
+        match tt {
+            TokenTree::Punct(ref punct)
+                if punct.as_char() == '#'
+                    && matches!(
+                        input.peek(),
+                        Some(TokenTree::Group(group))
+                            if group.delimiter() == Delimiter::Bracket
+                                && is_nested_marker(&group.stream())
+                    ) =>
+            {
+                let marker = input.next().expect("it was just peeked");
+
+                let mut call = TokenStream::new();
+                loop {
+                    let Some(tt) = input.next() else {
+                        output.error_combine(syn::Error::new(
+                            marker.span(),
+                            "expected a macro call after this",
+                        ));
+                        break;
+                    };
+                    let TokenTree::Group(group) = tt else {
+                        call.extend([tt]);
+                        continue;
+                    };
+                    match forward(group.stream(), None, forwarder) {
+                        Ok(stream) => {
+                            let mut new_group = Group::new(group.delimiter(), stream);
+                            new_group.set_span(group.span());
+                            call.extend([TokenTree::Group(new_group)]);
+                        }
+                        Err(err) => output.error_combine(err),
+                    }
+                    if group.delimiter() != Delimiter::Brace {
+                        if let Some(TokenTree::Punct(punct)) = input.peek() {
+                            if punct.as_char() == ';' {
+                                call.extend(input.next());
+                            }
+                        }
+                    }
+                    break;
+                }
+
+                if keeps_nested_instances {
+                    output.extend(call);
+                } else {
+                    // NOTE: there may be other attributes before the marker.
+                    output.extend(quote::quote!(
+                        const _: () = ();
+                    ));
+                }
+            }
+            TokenTree::Group(ref group) => {
+                match forward_into_nested_instances(
+                    group.stream(),
+                    forwarder,
+                    keeps_nested_instances,
+                ) {
+                    Ok(stream) => {
+                        let mut new_group = Group::new(group.delimiter(), stream);
+                        new_group.set_span(group.span());
+                        output.append(new_group);
+                    }
+                    Err(err) => output.error_combine(err),
+                }
+            }
+            tt => output.append(tt),
+        }
+    }
+
+    output.into_result()
+}
+
+/// `block` is the kind of the innermost block containing `input`, if it's a
+/// block where `__FORWARD` can be used.
+fn forward(
+    input: TokenStream,
+    block: Option<ForwardedKind>,
+    forwarder: &Forwarder,
+) -> Result<TokenStream, syn::Error> {
+    let mut input = input.into_iter().peekable();
+    let mut output = TokenStreamOrSynError::new();
+
+    let mut last_ident: Option<Ident> = None;
+    while let Some(tt) = input.next() {
+        match tt {
+            TokenTree::Ident(ref ident)
+                if ident == FORWARD_KEYWORD
+                    && matches!(
+                        input.peek(),
+                        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis
+                    ) =>
+            {
+                let Some(TokenTree::Group(group)) = input.next() else {
+                    unreachable!("it was just peeked")
+                };
+                let result = syn::parse2::<Ident>(group.stream()).and_then(|target_name_ident| {
+                    let Some(block) = block else {
+                        return Err(syn::Error::new(
+                            ident.span(),
+                            "`__FORWARD` can only be used in the `constructions` and `attribute_substitutions` blocks",
+                        ));
+                    };
+                    forwarder(block, &target_name_ident)
+                });
+                match result {
+                    Ok(forwarded) => output.extend(forwarded),
+                    Err(err) => output.error_combine(err),
+                }
+                last_ident = None;
+                continue;
+            }
+            TokenTree::Group(ref group) => {
+                let block_name = match &last_ident {
+                    Some(ident) if group.delimiter() == Delimiter::Brace => ident.to_string(),
+                    _ => String::new(),
+                };
+                let inner_block = match &block_name[..] {
+                    "constructions" => Some(ForwardedKind::Construction),
+                    "attribute_substitutions" => Some(ForwardedKind::AttributeSubstitution),
+                    _ => block,
+                };
+                match forward(group.stream(), inner_block, forwarder) {
+                    Ok(stream) => {
+                        let mut new_group = Group::new(group.delimiter(), stream);
+                        new_group.set_span(group.span());
+                        output.append(new_group);
+                    }
+                    Err(err) => output.error_combine(err),
+                }
+            }
+            ref tt => output.append(tt.clone()),
+        }
+
+        last_ident = match tt {
+            TokenTree::Ident(ident) => Some(ident),
+            _ => None,
+        };
+    }
+
+    output.into_result()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{forward_into_nested_instances, ForwardedKind};
+
+    #[test]
+    fn basic() {
+        let input = quote::quote! {
+            #[__NESTED]
+            define_foo! {
+                mod foo;
+                constructions { FOO => __FORWARD(BAR) },
+                attribute_substitutions { TEST => __FORWARD(TEST) },
+                matrix {
+                    x {
+                        one => { constructions { BAZ => Some(__FORWARD(BAR)) } },
+                    },
+                },
+            }
+            mod a_sub_mod {
+                #[__NESTED]
+                define_bar!(mod bar;);
+            }
+        };
+
+        let forwarder = |kind: ForwardedKind, target_name_ident: &syn::Ident| {
+            Ok(match kind {
+                ForwardedKind::Construction => quote::quote!(forwarded(#target_name_ident)),
+                ForwardedKind::AttributeSubstitution => quote::quote!(#[forwarded]),
+            })
+        };
+
+        let expected = quote::quote! {
+            define_foo! {
+                mod foo;
+                constructions { FOO => forwarded(BAR) },
+                attribute_substitutions { TEST => #[forwarded] },
+                matrix {
+                    x {
+                        one => { constructions { BAZ => Some(forwarded(BAR)) } },
+                    },
+                },
+            }
+            mod a_sub_mod {
+                define_bar!(mod bar;);
+            }
+        };
+
+        let actual = forward_into_nested_instances(input, &forwarder, true).unwrap();
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn forward_outside_blocks() {
+        let input = quote::quote! {
+            #[__NESTED]
+            define_foo! {
+                mod foo;
+                items { fn f() { __FORWARD(BAR); } },
+            }
+        };
+
+        let forwarder = |_: ForwardedKind, _: &syn::Ident| Ok(quote::quote!());
+
+        assert!(forward_into_nested_instances(input, &forwarder, true).is_err());
+    }
+}
@@ -1,22 +1,50 @@
 use std::collections::HashMap;
 
-use proc_macro2::{Delimiter, Group, TokenStream, TokenTree};
+use proc_macro2::{Delimiter, Group, Ident, Span, TokenStream, TokenTree};
 use quote::ToTokens;
 use syn::Meta;
 
-use super::token_stream_or_syn_error::TokenStreamOrSynError;
+use super::{nested_instances::is_nested_marker, token_stream_or_syn_error::TokenStreamOrSynError};
 
 pub type Substituter<'a> = dyn Fn(Meta) -> syn::Result<TokenStream> + 'a;
 
+/// Substitutes attributes whose paths are in `attr_map`, and identifiers marked
+/// as `__IDENT(NAME)` with the ones in `ident_map`, anywhere, including in the
+/// attributes that are not substituted. The name of an item with the attribute
+/// `#[__IDENT(NAME)]` is substituted as well, since `__IDENT(NAME)` can't be
+/// parsed there.
+///
+/// Macro calls marked with `#[__NESTED]` are kept as is, since they are
+/// processed by [`forward_into_nested_instances`] instead.
+///
+/// [`forward_into_nested_instances`]: super::nested_instances::forward_into_nested_instances
 pub fn substitute_attributes(
     input: TokenStream,
     attr_map: &mut HashMap<String, Box<Substituter>>,
+    ident_map: &HashMap<String, Ident>,
 ) -> Result<TokenStream, syn::Error> {
-    let mut input = input.into_iter();
+    let mut input = input.into_iter().peekable();
     let mut output = TokenStreamOrSynError::new();
+    // The name of the next item, set by `#[__IDENT(NAME)]`.
+    let mut item_name: Option<(Span, syn::Result<Ident>)> = None;
 
     'iteration: loop {
-        let Some(tt) = input.next() else {
+        let tt = input.next();
+        let is_item_end = match &tt {
+            None => true,
+            Some(TokenTree::Punct(punct)) => punct.as_char() == ';',
+            Some(TokenTree::Group(group)) => group.delimiter() == Delimiter::Brace,
+            Some(_) => false,
+        };
+        if is_item_end {
+            if let Some((span, _)) = item_name.take() {
+                output.error_combine(syn::Error::new(
+                    span,
+                    format!("`#[{}(..)]` should be put on a named item", IDENT_MARKER),
+                ));
+            }
+        }
+        let Some(tt) = tt else {
             break output.into_result();
         };
 
@@ -36,9 +64,44 @@ pub fn substitute_attributes(
                     output.extend([tt, tt_expect_group]);
                     continue 'iteration;
                 }
+                if is_nested_marker(&group.stream()) {
+                    output.extend([tt, tt_expect_group]);
+                    for tt in input.by_ref() {
+                        let is_args = matches!(tt, TokenTree::Group(..));
+                        let is_braced = matches!(&tt, TokenTree::Group(group) if group.delimiter() == Delimiter::Brace);
+                        output.append(tt);
+                        if !is_args {
+                            continue;
+                        }
+                        if !is_braced {
+                            if let Some(TokenTree::Punct(punct)) = input.peek() {
+                                if punct.as_char() == ';' {
+                                    output.extend(input.next());
+                                }
+                            }
+                        }
+                        break;
+                    }
+                    continue 'iteration;
+                }
+
+                if let Some(args) = ident_marker_args(&group.stream()) {
+                    if item_name.is_some() {
+                        output.error_combine(syn::Error::new(
+                            group.span(),
+                            format!("duplicate `#[{}(..)]`", IDENT_MARKER),
+                        ));
+                    }
+                    item_name = Some((group.span(), substitute_ident(args, ident_map)));
+                    continue 'iteration;
+                }
 
                 let Some(substituted) = try_substitute_attribute(group.stream(), attr_map) else {
-                    output.extend([tt, tt_expect_group]);
+                    output.append(tt);
+                    match substitute_idents(group, ident_map) {
+                        Ok(group) => output.append(group),
+                        Err(err) => output.error_combine(err),
+                    }
                     continue 'iteration;
                 };
                 match substituted {
@@ -46,8 +109,44 @@ pub fn substitute_attributes(
                     Err(err) => output.error_combine(err),
                 }
                 continue 'iteration;
+            } else if let TokenTree::Ident(ref ident) = tt {
+                if item_name.is_some() && ITEM_KEYWORDS.iter().any(|keyword| ident == keyword) {
+                    output.append(tt.clone());
+                    if *ident == "static" {
+                        output.extend(
+                            input.next_if(
+                                |tt| matches!(tt, TokenTree::Ident(ident) if ident == "mut"),
+                            ),
+                        );
+                    }
+                    let is_name = |tt: &TokenTree| matches!(tt, TokenTree::Ident(ident) if !ITEM_KEYWORDS.iter().any(|keyword| ident == keyword));
+                    if input.next_if(is_name).is_some() {
+                        match item_name.take() {
+                            Some((_, Ok(name))) => output.append(name),
+                            Some((_, Err(err))) => output.error_combine(err),
+                            None => {}
+                        }
+                    }
+                    continue 'iteration;
+                }
+                if *ident != IDENT_MARKER {
+                    break 'process;
+                }
+                let Some(TokenTree::Group(group)) = input.peek() else {
+                    break 'process;
+                };
+                if group.delimiter() != Delimiter::Parenthesis {
+                    break 'process;
+                }
+                let args = group.stream();
+                input.next();
+                match substitute_ident(args, ident_map) {
+                    Ok(substituted) => output.append(substituted),
+                    Err(err) => output.error_combine(err),
+                }
+                continue 'iteration;
             } else if let TokenTree::Group(ref group) = tt {
-                let inner_output = substitute_attributes(group.stream(), attr_map);
+                let inner_output = substitute_attributes(group.stream(), attr_map, ident_map);
                 match inner_output {
                     Ok(inner_output) => output.append(Group::new(group.delimiter(), inner_output)),
                     Err(inner_err) => output.error_combine(inner_err),
@@ -61,6 +160,74 @@ pub fn substitute_attributes(
     }
 }
 
+/// The marker of identifiers to be substituted, e.g. `__IDENT(NAME)`.
+const IDENT_MARKER: &str = "__IDENT";
+
+/// The keywords followed by the names of items, or by other keywords, e.g.
+/// `const fn`.
+const ITEM_KEYWORDS: &[&str] = &[
+    "const", "enum", "fn", "mod", "static", "struct", "trait", "type", "union",
+];
+
+/// Returns the arguments of the attribute `#[__IDENT(NAME)]`, given the tokens
+/// inside its brackets.
+fn ident_marker_args(attr: &TokenStream) -> Option<TokenStream> {
+    let mut tts = attr.clone().into_iter();
+    let (Some(TokenTree::Ident(ident)), Some(TokenTree::Group(group)), None) =
+        (tts.next(), tts.next(), tts.next())
+    else {
+        return None;
+    };
+    (ident == IDENT_MARKER && group.delimiter() == Delimiter::Parenthesis).then(|| group.stream())
+}
+
+fn substitute_ident(args: TokenStream, ident_map: &HashMap<String, Ident>) -> syn::Result<Ident> {
+    let target_name_ident: Ident = syn::parse2(args)?;
+    match ident_map.get(&target_name_ident.to_string()) {
+        Some(ident) => Ok(ident.clone()),
+        None => Err(syn::Error::new(
+            target_name_ident.span(),
+            format!(
+                "unknown target name `{}`. {} {}",
+                target_name_ident,
+                "It should be declared in the `idents` block",
+                "among the options of the attribute `mod_template::define`"
+            ),
+        )),
+    }
+}
+
+/// Substitutes only the marked identifiers in the group, e.g. in attributes
+/// that are not substituted.
+fn substitute_idents(group: &Group, ident_map: &HashMap<String, Ident>) -> syn::Result<Group> {
+    let mut input = group.stream().into_iter().peekable();
+    let mut output = TokenStreamOrSynError::new();
+    while let Some(tt) = input.next() {
+        match tt {
+            TokenTree::Ident(ref ident) if *ident == IDENT_MARKER => {
+                let Some(TokenTree::Group(args)) =
+                    input.next_if(|tt| matches!(tt, TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis))
+                else {
+                    output.append(tt);
+                    continue;
+                };
+                match substitute_ident(args.stream(), ident_map) {
+                    Ok(substituted) => output.append(substituted),
+                    Err(err) => output.error_combine(err),
+                }
+            }
+            TokenTree::Group(ref inner) => match substitute_idents(inner, ident_map) {
+                Ok(inner) => output.append(inner),
+                Err(err) => output.error_combine(err),
+            },
+            tt => output.append(tt),
+        }
+    }
+    let mut new_group = Group::new(group.delimiter(), output.into_result()?);
+    new_group.set_span(group.span());
+    Ok(new_group)
+}
+
 fn try_substitute_attribute(
     meta: TokenStream,
     attr_map: &mut HashMap<String, Box<Substituter>>,
@@ -68,9 +235,7 @@ fn try_substitute_attribute(
     let meta: Meta = syn::parse2(meta).ok()?;
 
     let path = meta.path().into_token_stream().to_string();
-    let Some(ref substituter) = attr_map.get(&path) else {
-        return None;
-    };
+    let substituter = attr_map.get(&path)?;
 
     Some(substituter(meta))
 }
@@ -134,7 +299,49 @@ mod tests {
             }
         };
 
-        let actual = substitute_attributes(input, &mut attr_map);
+        let actual = substitute_attributes(input, &mut attr_map, &HashMap::new());
+
+        assert_eq!(
+            expected.to_string(),
+            actual.expect("there should not be errors").to_string()
+        )
+    }
+
+    #[test]
+    fn idents() {
+        let input = quote::quote! {
+            #[foo]
+            #[__IDENT(FOO)]
+            #[doc(alias = __IDENT(FOO))]
+            pub(crate) unsafe extern "C" fn FOO() {
+                __IDENT(FOO)();
+                foo(FOO);
+                other::FOO();
+            }
+
+            #[__IDENT(BAR)]
+            static mut BAR: i32 = 0;
+        };
+
+        let mut attr_map: HashMap<String, Box<Substituter>> = HashMap::new();
+        attr_map.insert("foo".to_string(), Box::new(|_| Ok(quote::quote!(#[oof]))));
+        let mut ident_map = HashMap::new();
+        ident_map.insert("FOO".to_string(), quote::format_ident!("bar"));
+        ident_map.insert("BAR".to_string(), quote::format_ident!("baz"));
+
+        let expected = quote::quote! {
+            #[oof]
+            #[doc(alias = bar)]
+            pub(crate) unsafe extern "C" fn bar() {
+                bar();
+                foo(FOO);
+                other::FOO();
+            }
+
+            static mut baz: i32 = 0;
+        };
+
+        let actual = substitute_attributes(input, &mut attr_map, &ident_map);
 
         assert_eq!(
             expected.to_string(),
@@ -167,13 +374,49 @@ mod tests {
                 mod foo {}
             };
 
-            let actual = substitute_attributes(input, &mut attr_map);
+            let actual = substitute_attributes(input, &mut attr_map, &HashMap::new());
 
             let actual_error = actual.expect_err("should have an error");
 
             assert_eq!(actual_error.to_string(), "errored");
         }
 
+        #[test]
+        fn unknown_ident() {
+            let input = quote::quote! {
+                #[__IDENT(FOO)]
+                fn foo() {}
+            };
+
+            let actual = substitute_attributes(input, &mut HashMap::new(), &HashMap::new());
+
+            let actual_error = actual.expect_err("should have an error");
+
+            assert_eq!(
+                actual_error.to_string(),
+                "unknown target name `FOO`. It should be declared in the `idents` block among the options of the attribute `mod_template::define`"
+            );
+        }
+
+        #[test]
+        fn unnamed_item() {
+            let input = quote::quote! {
+                #[__IDENT(FOO)]
+                impl Foo {}
+            };
+
+            let mut ident_map = HashMap::new();
+            ident_map.insert("FOO".to_string(), quote::format_ident!("foo"));
+            let actual = substitute_attributes(input, &mut HashMap::new(), &ident_map);
+
+            let actual_error = actual.expect_err("should have an error");
+
+            assert_eq!(
+                actual_error.to_string(),
+                "`#[__IDENT(..)]` should be put on a named item"
+            );
+        }
+
         #[test]
         fn multiple() {
             let (mut attr_map,) = before_each();
@@ -186,7 +429,7 @@ mod tests {
                 }
             };
 
-            let actual = substitute_attributes(input, &mut attr_map);
+            let actual = substitute_attributes(input, &mut attr_map, &HashMap::new());
 
             // TODO: how to assert error message of combined syn::Error?
             let _actual_error = actual.expect_err("should have an error");
@@ -0,0 +1,35 @@
+pub trait Sumer {
+    fn sum(&self, iter: Box<dyn Iterator<Item = i32>>) -> i32;
+}
+
+#[mod_template::define(
+    #[macro_export]
+    macro_rules! define_sumer_test_suite;
+    constructions(SUMER -> Box<dyn __CRATE::Sumer>),
+    attribute_substitutions(TEST),
+    types(Input: IntoIterator<Item = i32> + Default),
+)]
+mod __ {
+    #[allow(unused_imports)]
+    use __CRATE::Sumer;
+
+    macro_rules! assert_sum {
+        ($sumer:expr, [$($item:expr),*] => $expected:expr) => {
+            assert_eq!($sumer.sum(Box::new(vec![$($item),*].into_iter())), $expected)
+        };
+    }
+
+    #[__CONSTRUCT(sumer as SUMER)]
+    #[__SUBSTITUTE(TEST)]
+    pub fn it_works() {
+        let input: Input = Default::default();
+        let input: Vec<i32> = input.into_iter().collect();
+        assert_eq!(sumer.sum(Box::new(input.into_iter())), 0)
+    }
+
+    #[__CONSTRUCT(sumer as SUMER)]
+    #[__SUBSTITUTE(TEST)]
+    pub fn it_works_with_items() {
+        assert_sum!(sumer, [1, 2, 3] => 6);
+    }
+}
@@ -0,0 +1,29 @@
+use mod_template::extend_field_list;
+
+fn main() {}
+
+#[extend_field_list(.., pub handle: u32)]
+#[derive(Debug, Default)]
+struct Connection {
+    name: String,
+}
+
+#[test]
+fn test_connection() {
+    let connection = Connection {
+        name: "primary".to_string(),
+        handle: 42,
+    };
+    assert_eq!(connection.name, "primary");
+    assert_eq!(connection.handle, 42);
+    assert_eq!(Connection::default().handle, 0);
+}
+
+#[extend_field_list(.., u32)]
+struct Pair(u32);
+
+#[test]
+fn test_pair() {
+    let Pair(a, b) = Pair(1, 2);
+    assert_eq!(a + b, 3);
+}
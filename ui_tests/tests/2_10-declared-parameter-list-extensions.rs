@@ -0,0 +1,39 @@
+fn main() {}
+
+struct Multiplier(i32);
+
+#[mod_template::define(
+    macro_rules! define_multiplying_mod;
+    constructions(MULTIPLIER -> crate::Multiplier = crate::Multiplier(factor)),
+    attribute_substitutions(WITH_FACTOR(.., factor: i32)),
+)]
+mod __ {
+    #[__CONSTRUCT(multiplier as MULTIPLIER)]
+    #[__SUBSTITUTE(WITH_FACTOR)]
+    pub fn multiply(n: i32) -> i32 {
+        n * multiplier.0
+    }
+}
+
+define_multiplying_mod! {
+    mod by_factor;
+    attribute_substitutions {
+        WITH_FACTOR => (.., factor: i32),
+    },
+}
+
+define_multiplying_mod! {
+    mod by_factor_plus_offset;
+    constructions {
+        MULTIPLIER => crate::Multiplier(factor + offset),
+    },
+    attribute_substitutions {
+        WITH_FACTOR => #[inline] (.., factor: i32, offset: i32),
+    },
+}
+
+#[test]
+fn declared_parameter_list_extensions() {
+    assert_eq!(by_factor::multiply(2, 3), 6);
+    assert_eq!(by_factor_plus_offset::multiply(2, 3, 1), 8);
+}
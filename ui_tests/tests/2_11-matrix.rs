@@ -0,0 +1,72 @@
+fn main() {}
+
+trait Codec {
+    fn encode(&self, n: u32) -> Vec<u8>;
+    fn decode(&self, bytes: &[u8]) -> u32;
+}
+
+struct BigEndian;
+impl Codec for BigEndian {
+    fn encode(&self, n: u32) -> Vec<u8> {
+        n.to_be_bytes().to_vec()
+    }
+    fn decode(&self, bytes: &[u8]) -> u32 {
+        u32::from_be_bytes(bytes.try_into().unwrap())
+    }
+}
+
+struct LittleEndian;
+impl Codec for LittleEndian {
+    fn encode(&self, n: u32) -> Vec<u8> {
+        n.to_le_bytes().to_vec()
+    }
+    fn decode(&self, bytes: &[u8]) -> u32 {
+        u32::from_le_bytes(bytes.try_into().unwrap())
+    }
+}
+
+#[mod_template::define(
+    macro_rules! define_codec_test_suite;
+    constructions(CODEC -> Box<dyn crate::Codec>, NUMBER -> u32),
+    attribute_substitutions(TEST),
+)]
+mod __ {
+    #[allow(unused_imports)]
+    use crate::Codec;
+
+    #[__CONSTRUCT(codec as CODEC, number as NUMBER)]
+    #[__SUBSTITUTE(TEST)]
+    pub fn round_trip() {
+        assert_eq!(codec.decode(&codec.encode(number)), number);
+    }
+}
+
+define_codec_test_suite! {
+    mod codec_test_suite;
+    attribute_substitutions {
+        TEST => #[test],
+    },
+    matrix {
+        codec {
+            big_endian => { constructions { CODEC => Box::new(crate::BigEndian) } },
+            little_endian => { constructions { CODEC => Box::new(crate::LittleEndian) } },
+        },
+        number {
+            zero => { constructions { NUMBER => 0 } },
+            max => { constructions { NUMBER => u32::MAX } },
+            answer => { constructions { NUMBER => 42 } },
+        },
+    },
+}
+
+#[test]
+fn generated_module_names() {
+    let _: [fn(); 6] = [
+        codec_test_suite_big_endian_zero::round_trip,
+        codec_test_suite_big_endian_max::round_trip,
+        codec_test_suite_big_endian_answer::round_trip,
+        codec_test_suite_little_endian_zero::round_trip,
+        codec_test_suite_little_endian_max::round_trip,
+        codec_test_suite_little_endian_answer::round_trip,
+    ];
+}
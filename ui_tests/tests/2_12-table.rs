@@ -0,0 +1,74 @@
+fn main() {}
+
+trait Sumer {
+    fn sum(&self, iter: Box<dyn Iterator<Item = i32>>) -> i32;
+}
+
+struct ImperativeSumer;
+impl Sumer for ImperativeSumer {
+    fn sum(&self, iter: Box<dyn Iterator<Item = i32>>) -> i32 {
+        let mut sum = 0;
+        for item in iter {
+            sum += item
+        }
+        sum
+    }
+}
+
+struct FunctionalSumer;
+impl Sumer for FunctionalSumer {
+    fn sum(&self, iter: Box<dyn Iterator<Item = i32>>) -> i32 {
+        iter.reduce(|a, b| a + b).unwrap_or(0)
+    }
+}
+
+struct BuiltinSumer;
+impl Sumer for BuiltinSumer {
+    fn sum(&self, iter: Box<dyn Iterator<Item = i32>>) -> i32 {
+        iter.sum()
+    }
+}
+
+#[mod_template::define(
+    macro_rules! define_sumer_test_suite;
+    constructions(SUMER -> Box<dyn crate::Sumer>),
+    attribute_substitutions(TEST),
+)]
+mod __ {
+    #[allow(unused_imports)]
+    use crate::Sumer;
+
+    #[__CONSTRUCT(sumer as SUMER)]
+    #[__SUBSTITUTE(TEST)]
+    pub fn it_works() {
+        let input = vec![1, 2, 3];
+        assert_eq!(sumer.sum(Box::new(input.into_iter())), 6)
+    }
+}
+
+define_sumer_test_suite! {
+    constructions {
+        SUMER => Box::new(crate::BuiltinSumer),
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+    mods {
+        mod imperative_sumer_test_suite => {
+            constructions { SUMER => Box::new(crate::ImperativeSumer) },
+        },
+        mod functional_sumer_test_suite => {
+            constructions { SUMER => Box::new(crate::FunctionalSumer) },
+        },
+        pub mod builtin_sumer_test_suite => {},
+    },
+}
+
+#[test]
+fn generated_modules() {
+    let _: [fn(); 3] = [
+        imperative_sumer_test_suite::it_works,
+        functional_sumer_test_suite::it_works,
+        builtin_sumer_test_suite::it_works,
+    ];
+}
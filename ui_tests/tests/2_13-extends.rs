@@ -0,0 +1,111 @@
+fn main() {}
+
+trait Store {
+    fn get(&self, key: &str) -> Option<i32>;
+    fn set(&mut self, key: &str, value: i32);
+}
+
+trait TransactionalStore: Store {
+    fn commit(&mut self);
+    fn rollback(&mut self);
+}
+
+#[derive(Default)]
+struct MemoryStore {
+    committed: std::collections::HashMap<String, i32>,
+    pending: std::collections::HashMap<String, i32>,
+}
+impl Store for MemoryStore {
+    fn get(&self, key: &str) -> Option<i32> {
+        (self.pending.get(key))
+            .or_else(|| self.committed.get(key))
+            .copied()
+    }
+    fn set(&mut self, key: &str, value: i32) {
+        self.pending.insert(key.to_string(), value);
+    }
+}
+impl TransactionalStore for MemoryStore {
+    fn commit(&mut self) {
+        self.committed.extend(self.pending.drain());
+    }
+    fn rollback(&mut self) {
+        self.pending.clear();
+    }
+}
+
+#[mod_template::define(
+    macro_rules! define_store_test_suite;
+    constructions(STORE -> impl crate::Store),
+    attribute_substitutions(TEST),
+)]
+mod __ {
+    #[allow(unused_imports)]
+    use crate::Store;
+
+    #[__CONSTRUCT(mut store as STORE)]
+    #[__SUBSTITUTE(TEST)]
+    pub fn set_then_get() {
+        store.set("answer", 42);
+        assert_eq!(store.get("answer"), Some(42));
+    }
+}
+
+#[mod_template::define(
+    macro_rules! define_tx_store_test_suite;
+    extends(define_store_test_suite),
+    constructions(TX_STORE -> impl crate::TransactionalStore),
+    attribute_substitutions(TX_TEST = #[test]),
+)]
+mod __ {
+    #[allow(unused_imports)]
+    use crate::TransactionalStore;
+
+    #[__CONSTRUCT(mut store as TX_STORE)]
+    #[__SUBSTITUTE(TX_TEST)]
+    pub fn rollback_discards_changes() {
+        store.set("answer", 42);
+        store.rollback();
+        assert_eq!(store.get("answer"), None);
+    }
+
+    #[__CONSTRUCT(mut store as TX_STORE)]
+    #[__SUBSTITUTE(TEST)]
+    pub fn commit_keeps_changes() {
+        store.set("answer", 42);
+        store.commit();
+        store.rollback();
+        assert_eq!(store.get("answer"), Some(42));
+    }
+}
+
+define_tx_store_test_suite! {
+    mod memory_store_test_suite;
+    constructions {
+        STORE => crate::MemoryStore::default(),
+        TX_STORE => crate::MemoryStore::default(),
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+}
+
+define_store_test_suite! {
+    mod memory_store_base_test_suite;
+    constructions {
+        STORE => crate::MemoryStore::default(),
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+}
+
+#[test]
+fn generated_items() {
+    let _: [fn(); 3] = [
+        memory_store_test_suite::set_then_get,
+        memory_store_test_suite::rollback_discards_changes,
+        memory_store_test_suite::commit_keeps_changes,
+    ];
+    let _: fn() = memory_store_base_test_suite::set_then_get;
+}
@@ -0,0 +1,67 @@
+fn main() {}
+
+trait Greeter {
+    fn greet(&self, name: &str) -> String;
+}
+
+struct EnglishGreeter;
+impl Greeter for EnglishGreeter {
+    fn greet(&self, name: &str) -> String {
+        format!("Hello, {}!", name)
+    }
+}
+
+#[mod_template::define(
+    macro_rules! define_smoke_test_suite;
+    constructions(SUBJECT -> Box<dyn crate::Greeter>),
+    attribute_substitutions(TEST),
+)]
+mod __ {
+    #[__CONSTRUCT(subject as SUBJECT)]
+    #[__SUBSTITUTE(TEST)]
+    pub fn it_does_not_panic() {
+        subject.greet("");
+    }
+}
+
+#[mod_template::define(
+    macro_rules! define_greeter_test_suite;
+    constructions(GREETER -> Box<dyn crate::Greeter>),
+    attribute_substitutions(TEST),
+)]
+mod __ {
+    #[__CONSTRUCT(greeter as GREETER)]
+    #[__SUBSTITUTE(TEST)]
+    pub fn it_greets() {
+        assert_eq!(greeter.greet("world"), "Hello, world!");
+    }
+
+    #[__NESTED]
+    define_smoke_test_suite! {
+        pub mod smoke;
+        constructions {
+            SUBJECT => __FORWARD(GREETER),
+        },
+        attribute_substitutions {
+            TEST => __FORWARD(TEST),
+        },
+    }
+}
+
+define_greeter_test_suite! {
+    mod english_greeter_test_suite;
+    constructions {
+        GREETER => Box::new(crate::EnglishGreeter),
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+}
+
+#[test]
+fn generated_items() {
+    let _: [fn(); 2] = [
+        english_greeter_test_suite::it_greets,
+        english_greeter_test_suite::smoke::it_does_not_panic,
+    ];
+}
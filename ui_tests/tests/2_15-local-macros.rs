@@ -0,0 +1,70 @@
+fn main() {}
+
+#[mod_template::define(
+    macro_rules! define_sum_test_suite;
+    constructions(SUM -> fn(&[i32]) -> i32),
+    attribute_substitutions(TEST),
+)]
+mod __ {
+    #[allow(unused_macros)]
+    macro_rules! assert_sum {
+        ($sum:expr, [$($item:expr),*] => $expected:expr) => {
+            assert_eq!($sum(&[$($item),*]), $expected)
+        };
+    }
+
+    #[__CONSTRUCT(sum as SUM)]
+    #[__SUBSTITUTE(TEST)]
+    pub fn it_works() {
+        assert_sum!(sum, [] => 0);
+        assert_sum!(sum, [1, 2, 3] => 6);
+    }
+}
+
+#[mod_template::define(
+    macro_rules! define_more_sum_test_suite;
+    extends(define_sum_test_suite),
+)]
+mod __ {
+    #[allow(unused_macros)]
+    macro_rules! sum_of {
+        ($sum:expr, $($item:expr),*) => {
+            $sum(&[$($item),*])
+        };
+    }
+
+    #[__CONSTRUCT(sum as SUM)]
+    #[__SUBSTITUTE(TEST)]
+    pub fn it_works_with_negative_numbers() {
+        assert_eq!(sum_of!(sum, -1, -2), -3);
+    }
+}
+
+define_sum_test_suite! {
+    mod iter_sum_test_suite;
+    constructions {
+        SUM => |items| items.iter().sum(),
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+}
+
+define_more_sum_test_suite! {
+    mod more_iter_sum_test_suite;
+    constructions {
+        SUM => |items| items.iter().sum(),
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+}
+
+#[test]
+fn generated_items() {
+    let _: [fn(); 3] = [
+        iter_sum_test_suite::it_works,
+        more_iter_sum_test_suite::it_works,
+        more_iter_sum_test_suite::it_works_with_negative_numbers,
+    ];
+}
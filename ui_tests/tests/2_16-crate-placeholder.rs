@@ -0,0 +1,21 @@
+fn main() {}
+
+pub struct BuiltinSumer;
+impl shared_templates::Sumer for BuiltinSumer {
+    fn sum(&self, iter: Box<dyn Iterator<Item = i32>>) -> i32 {
+        iter.sum()
+    }
+}
+
+shared_templates::define_sumer_test_suite! {
+    mod builtin_sumer_test_suite;
+    constructions {
+        SUMER => Box::new(crate::BuiltinSumer),
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+    types {
+        Input => Vec<i32>,
+    },
+}
@@ -0,0 +1,34 @@
+fn main() {}
+
+pub mod facade {
+    pub use ::mod_template as templates;
+}
+
+#[facade::templates::define(
+    macro_rules! define_answer_test_suite;
+    crate = __CRATE::facade::templates,
+    constructions(ANSWER -> i32),
+    attribute_substitutions(WITH_EXPECTED(.., expected: i32)),
+)]
+mod __ {
+    #[__CONSTRUCT(answer as ANSWER)]
+    #[__SUBSTITUTE(WITH_EXPECTED)]
+    pub fn check(n: i32) {
+        assert_eq!(answer + n, expected);
+    }
+}
+
+define_answer_test_suite! {
+    mod answer_test_suite;
+    constructions {
+        ANSWER => 42,
+    },
+    attribute_substitutions {
+        WITH_EXPECTED => (.., expected: i32),
+    },
+}
+
+#[test]
+fn generated_items() {
+    answer_test_suite::check(1, 43);
+}
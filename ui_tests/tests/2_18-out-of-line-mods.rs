@@ -0,0 +1,39 @@
+fn main() {}
+
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[mod_template::define(
+    macro_rules! define_adder_test_suite;
+    constructions(ADD -> fn(i32, i32) -> i32),
+    attribute_substitutions(TEST),
+)]
+mod __ {
+    #[__FROM_FILE("2_18-out-of-line-mods/cases.rs")]
+    pub mod cases {}
+}
+
+define_adder_test_suite! {
+    mod adder_test_suite;
+    constructions {
+        ADD => crate::add,
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+}
+
+#[path = "2_18-out-of-line-mods"]
+mod in_dir {
+    pub mod non_mod_rs;
+}
+
+#[test]
+fn generated_items() {
+    let _: [fn(); 3] = [
+        adder_test_suite::cases::adds_zero,
+        adder_test_suite::cases::commutative::is_commutative,
+        in_dir::non_mod_rs::multiplier_test_suite::cases::multiplies_by_one,
+    ];
+}
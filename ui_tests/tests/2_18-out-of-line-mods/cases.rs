@@ -0,0 +1,10 @@
+//! Loaded by `2_18-out-of-line-mods.rs`.
+
+#[__CONSTRUCT(add as ADD)]
+#[__SUBSTITUTE(TEST)]
+pub fn adds_zero() {
+    assert_eq!(add(1, 0), 1);
+}
+
+#[__FROM_FILE]
+pub mod commutative {}
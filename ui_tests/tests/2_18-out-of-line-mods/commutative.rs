@@ -0,0 +1,5 @@
+#[__CONSTRUCT(add as ADD)]
+#[__SUBSTITUTE(TEST)]
+pub fn is_commutative() {
+    assert_eq!(add(1, 2), add(2, 1));
+}
@@ -0,0 +1,26 @@
+//! Loaded by `2_18-out-of-line-mods.rs`. Like in `mod cases;`, `cases` is
+//! loaded from `non_mod_rs/cases.rs`, since this file isn't a `mod.rs`.
+
+fn mul(a: i32, b: i32) -> i32 {
+    a * b
+}
+
+#[mod_template::define(
+    macro_rules! define_multiplier_test_suite;
+    constructions(MUL -> fn(i32, i32) -> i32),
+    attribute_substitutions(TEST),
+)]
+mod __ {
+    #[__FROM_FILE]
+    pub mod cases {}
+}
+
+define_multiplier_test_suite! {
+    pub mod multiplier_test_suite;
+    constructions {
+        MUL => crate::in_dir::non_mod_rs::mul,
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+}
@@ -0,0 +1,7 @@
+//! Loaded by `2_18-out-of-line-mods/non_mod_rs.rs`.
+
+#[__CONSTRUCT(mul as MUL)]
+#[__SUBSTITUTE(TEST)]
+pub fn multiplies_by_one() {
+    assert_eq!(mul(2, 1), 2);
+}
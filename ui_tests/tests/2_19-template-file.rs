@@ -0,0 +1,31 @@
+fn main() {}
+
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[mod_template::define(
+    macro_rules! define_adder_test_suite;
+    path = "2_19-template-file/template.rs",
+    constructions(ADD -> fn(i32, i32) -> i32),
+    attribute_substitutions(TEST),
+)]
+mod __ {}
+
+define_adder_test_suite! {
+    mod adder_test_suite;
+    constructions {
+        ADD => crate::add,
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+}
+
+#[test]
+fn generated_items() {
+    let _: [fn(); 2] = [
+        adder_test_suite::adds_zero,
+        adder_test_suite::cases::is_commutative,
+    ];
+}
@@ -0,0 +1,10 @@
+//! The template of `define_adder_test_suite`, loaded by `2_19-template-file.rs`.
+
+#[__CONSTRUCT(add as ADD)]
+#[__SUBSTITUTE(TEST)]
+pub fn adds_zero() {
+    assert_eq!(add(1, 0), 1);
+}
+
+#[__FROM_FILE]
+pub mod cases {}
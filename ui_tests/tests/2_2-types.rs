@@ -0,0 +1,221 @@
+fn main() {}
+
+trait Shape {
+    fn new(size: f64) -> Self;
+    fn area(&self) -> f64;
+}
+
+struct Square(f64);
+impl Shape for Square {
+    fn new(size: f64) -> Self {
+        Self(size)
+    }
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+
+struct Circle(f64);
+impl Shape for Circle {
+    fn new(size: f64) -> Self {
+        Self(size)
+    }
+    fn area(&self) -> f64 {
+        3.0 * self.0 * self.0
+    }
+}
+
+#[mod_template::define(
+    macro_rules! define_shape_test_suite;
+    constructions(SHAPE -> TheShape),
+    attribute_substitutions(TEST),
+    types(TheShape: crate::Shape),
+)]
+mod __ {
+    // FIXME: `cargo test` thinks this is unused. See test `2_1` for details.
+    #[allow(unused_imports)]
+    use crate::Shape;
+
+    #[allow(dead_code)]
+    struct Pair {
+        a: TheShape,
+        b: TheShape,
+    }
+
+    fn twice_as_large(shape: &TheShape) -> TheShape {
+        TheShape::new(shape.area() * 2.0)
+    }
+
+    #[__CONSTRUCT(shape as SHAPE)]
+    #[__SUBSTITUTE(TEST)]
+    fn it_works() {
+        let larger = twice_as_large(&shape);
+        assert!(shape.area() < larger.area());
+    }
+
+    mod sub {
+        #[allow(dead_code)]
+        fn sized() -> usize {
+            std::mem::size_of::<TheShape>()
+        }
+    }
+}
+
+define_shape_test_suite! {
+    mod square_test_suite;
+    constructions {
+        SHAPE => crate::Square(1.0),
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+    types {
+        TheShape => crate::Square,
+    },
+}
+
+define_shape_test_suite! {
+    mod circle_test_suite;
+    constructions {
+        SHAPE => crate::Circle(1.0),
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+    types {
+        TheShape => crate::Circle,
+    },
+}
+
+trait Store {
+    fn get(&self) -> i32;
+}
+
+struct Memory(i32);
+impl Store for Memory {
+    fn get(&self) -> i32 {
+        self.0
+    }
+}
+
+#[mod_template::define(
+    macro_rules! define_wrapper_mod;
+    types(Backend: crate::Store),
+)]
+mod __ {
+    // FIXME: `cargo test` thinks this is unused. See test `2_1` for details.
+    #[allow(unused_imports)]
+    use crate::Store;
+
+    pub struct Wrapper {
+        pub backend: Backend,
+    }
+
+    impl Wrapper {
+        pub fn get(&self) -> &Backend {
+            &self.backend
+        }
+
+        pub fn value(&self) -> i32 {
+            self.backend.get()
+        }
+    }
+
+    pub fn wrap(backend: Backend) -> Wrapper {
+        Wrapper { backend }
+    }
+}
+
+define_wrapper_mod! {
+    mod memory_wrapper;
+    types {
+        Backend => crate::Memory,
+    },
+}
+
+#[test]
+fn wrapper() {
+    let wrapper = memory_wrapper::wrap(Memory(42));
+    assert_eq!(wrapper.value(), 42);
+    assert_eq!(wrapper.get().0, 42);
+}
+
+#[mod_template::define(
+    macro_rules! define_shape_harness_test_suite;
+    types(TheShape: crate::Shape),
+)]
+mod __ {
+    // FIXME: `cargo test` thinks this is unused. See test `2_1` for details.
+    #[allow(unused_imports)]
+    use crate::Shape;
+
+    fn make() -> TheShape {
+        TheShape::new(1.0)
+    }
+
+    struct Harness {
+        shape: TheShape,
+    }
+
+    fn make_harness() -> Harness {
+        Harness { shape: make() }
+    }
+
+    #[test]
+    fn area_is_positive() {
+        assert!(make().area() > 0.0);
+    }
+
+    #[test]
+    fn harness_works() {
+        let harness = make_harness();
+        assert!(harness.shape.area() > 0.0);
+    }
+
+    mod glob_imported {
+        use super::*;
+
+        #[test]
+        fn area_is_positive() {
+            assert!(make().area() > 0.0);
+        }
+    }
+
+    mod unrelated {
+        fn make() -> u8 {
+            1
+        }
+
+        #[test]
+        fn not_a_shape() {
+            assert_eq!(make(), 1);
+        }
+    }
+
+    mod shadowed {
+        struct TheShape(u8);
+
+        fn make() -> TheShape {
+            TheShape(1)
+        }
+
+        #[test]
+        fn not_a_shape() {
+            assert_eq!(make().0, 1);
+        }
+    }
+}
+
+define_shape_harness_test_suite! {
+    mod square_harness_test_suite;
+    types {
+        TheShape => crate::Square,
+    },
+}
+
+define_shape_harness_test_suite! {
+    mod circle_harness_test_suite;
+    types {
+        TheShape => crate::Circle,
+    },
+}
@@ -0,0 +1,87 @@
+fn main() {}
+
+trait Stack {
+    fn push(&mut self, value: i32);
+    fn pop(&mut self) -> Option<i32>;
+}
+
+#[derive(Default)]
+struct VecStack(Vec<i32>);
+impl Stack for VecStack {
+    fn push(&mut self, value: i32) {
+        self.0.push(value)
+    }
+    fn pop(&mut self) -> Option<i32> {
+        self.0.pop()
+    }
+}
+
+#[mod_template::define(
+    macro_rules! define_lifo_test;
+    constructions(STACK -> TheStack),
+    attribute_substitutions(TEST),
+    types(TheStack: crate::Stack),
+    consts(COUNT: i32),
+)]
+#[__CONSTRUCT(mut stack as STACK)]
+#[__SUBSTITUTE(TEST)]
+fn __() {
+    // FIXME: `cargo test` thinks this is unused. See test `2_1` for details.
+    #[allow(unused_imports)]
+    use crate::Stack;
+
+    let _: &TheStack = &stack;
+    let count = COUNT;
+    for i in 0..count {
+        stack.push(i);
+    }
+    for i in (0..count).rev() {
+        assert_eq!(stack.pop(), Some(i));
+    }
+    assert_eq!(stack.pop(), None);
+}
+
+define_lifo_test! {
+    /// Instances of a template of a function are functions.
+    pub fn vec_stack_is_lifo;
+    constructions {
+        STACK => crate::VecStack::default(),
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+    types {
+        TheStack => crate::VecStack,
+    },
+    consts {
+        COUNT => 3,
+    },
+}
+
+define_lifo_test! {
+    fn vec_stack_is_lifo_for;
+    constructions {
+        STACK => crate::VecStack::default(),
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+    types {
+        TheStack => crate::VecStack,
+    },
+    matrix {
+        count {
+            one => { consts { COUNT => 1 } },
+            many => { consts { COUNT => 100 } },
+        },
+    },
+}
+
+#[test]
+fn generated_items() {
+    let _: [fn(); 3] = [
+        vec_stack_is_lifo,
+        vec_stack_is_lifo_for_one,
+        vec_stack_is_lifo_for_many,
+    ];
+}
@@ -0,0 +1,116 @@
+fn main() {}
+
+trait Unit {
+    fn symbol(&self) -> &'static str;
+    fn precision(&self) -> usize;
+}
+
+struct Celsius;
+struct Fahrenheit;
+struct Reading<U>(f64, U);
+
+#[mod_template::define(
+    macro_rules! impl_unit;
+    constructions(SYMBOL -> &'static str),
+    consts(PRECISION: usize),
+)]
+impl crate::Unit for __ {
+    #[__CONSTRUCT(symbol as SYMBOL)]
+    fn symbol(&self) -> &'static str {
+        symbol
+    }
+
+    fn precision(&self) -> usize {
+        PRECISION
+    }
+}
+
+impl_unit! {
+    impl crate::Celsius;
+    constructions {
+        SYMBOL => "°C",
+    },
+    consts {
+        PRECISION => 1,
+    },
+}
+
+impl_unit! {
+    impl crate::Fahrenheit;
+    constructions {
+        SYMBOL => "°F",
+    },
+    consts {
+        PRECISION => 0,
+    },
+}
+
+#[mod_template::define(
+    macro_rules! impl_kind;
+    constructions(KIND -> &'static str),
+)]
+impl __ {
+    #[__CONSTRUCT(kind as KIND)]
+    pub fn kind() -> &'static str {
+        kind
+    }
+}
+
+impl_kind! {
+    impl<U> crate::Reading<U> where U: crate::Unit;
+    constructions {
+        KIND => "reading",
+    },
+}
+
+impl<U: Unit> Reading<U> {
+    fn describe(&self) -> String {
+        format!("{:.*}{}", self.1.precision(), self.0, self.1.symbol())
+    }
+}
+
+trait Describe: std::fmt::Display + std::error::Error {
+    fn describe(&self) -> String;
+}
+
+#[derive(Debug)]
+struct Timeout;
+impl std::fmt::Display for Timeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "timed out")
+    }
+}
+
+#[mod_template::define(macro_rules! impl_error;)]
+impl std::error::Error for __ {}
+
+impl_error! {
+    impl crate::Timeout;
+}
+
+#[mod_template::define(
+    macro_rules! impl_describe;
+    constructions(PREFIX -> &'static str),
+)]
+impl crate::Describe for __ {
+    #[__CONSTRUCT(prefix as PREFIX)]
+    fn describe(&self) -> String {
+        let source = std::error::Error::source(self).map(|x| x.to_string());
+        format!("{}: {} ({:?})", prefix, self, source)
+    }
+}
+
+impl_describe! {
+    impl crate::Timeout;
+    constructions {
+        PREFIX => "error",
+    },
+}
+
+#[test]
+fn generated_items() {
+    assert_eq!(Reading(21.5, Celsius).describe(), "21.5°C");
+    assert_eq!(Reading(70.7, Fahrenheit).describe(), "71°F");
+    assert_eq!(Reading::<Celsius>::kind(), "reading");
+    assert_eq!(Timeout.describe(), "error: timed out (None)");
+}
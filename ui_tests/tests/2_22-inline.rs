@@ -0,0 +1,142 @@
+fn main() {}
+
+trait Greeter {
+    fn greet(&self, name: &str) -> String;
+}
+
+struct EnglishGreeter;
+impl Greeter for EnglishGreeter {
+    fn greet(&self, name: &str) -> String {
+        format!("Hello, {}!", name)
+    }
+}
+
+#[mod_template::define(
+    macro_rules! define_greeter_tests;
+    constructions(GREETER -> Box<dyn crate::Greeter>),
+    attribute_substitutions(TEST),
+    idents(GREETS),
+)]
+mod __ {
+    #[__CONSTRUCT(greeter as GREETER)]
+    #[__SUBSTITUTE(TEST)]
+    #[__IDENT(GREETS)]
+    pub fn greets() {
+        assert_eq!(greeter.greet("world"), "Hello, world!");
+    }
+}
+
+define_greeter_tests! {
+    mod _;
+    constructions {
+        GREETER => Box::new(crate::EnglishGreeter),
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+    idents {
+        GREETS => english_greeter_greets,
+    },
+}
+
+mod nested {
+    define_greeter_tests! {
+        mod _;
+        constructions {
+            GREETER => Box::new(crate::EnglishGreeter),
+        },
+        attribute_substitutions {
+            TEST => #[test],
+        },
+        idents {
+            GREETS => english_greeter_greets,
+        },
+    }
+}
+
+trait Store {
+    fn get(&self) -> i32;
+}
+
+#[derive(Default)]
+struct Memory;
+impl Store for Memory {
+    fn get(&self) -> i32 {
+        42
+    }
+}
+
+#[mod_template::define(
+    macro_rules! define_store_tests;
+    types(Backend: crate::Store + Default),
+    consts(EXPECTED: i32),
+    attribute_substitutions(TEST),
+    idents(READS, BACKENDS),
+)]
+mod __ {
+    #[__SUBSTITUTE(TEST)]
+    #[__IDENT(READS)]
+    pub fn reads() {
+        let backend = Backend::default();
+        assert_eq!(crate::Store::get(&backend), EXPECTED);
+    }
+
+    #[__IDENT(BACKENDS)]
+    mod backends {
+        fn make() -> Backend {
+            Backend::default()
+        }
+
+        #[__SUBSTITUTE(TEST)]
+        fn makes() {
+            assert_eq!(crate::Store::get(&make()), EXPECTED);
+        }
+    }
+}
+
+// NOTE: the declared types and consts are not put into this scope, so they
+// neither collide with these items nor with each other.
+#[allow(dead_code)]
+struct Backend;
+const EXPECTED: i32 = 0;
+
+define_store_tests! {
+    mod _;
+    types {
+        Backend => crate::Memory,
+    },
+    consts {
+        EXPECTED => 42,
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+    idents {
+        READS => memory_reads,
+        BACKENDS => memory_backends,
+    },
+}
+
+define_store_tests! {
+    mod _;
+    types {
+        Backend => crate::Memory,
+    },
+    consts {
+        EXPECTED => 42,
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+    idents {
+        READS => memory_reads_again,
+        BACKENDS => more_memory_backends,
+    },
+}
+
+#[test]
+fn generated_items() {
+    let _: [fn(); 2] = [english_greeter_greets, nested::english_greeter_greets];
+    let _: [fn(); 2] = [memory_reads, memory_reads_again];
+    assert_eq!(EXPECTED, 0);
+}
@@ -0,0 +1,93 @@
+fn main() {}
+
+trait Shape {
+    fn new(size: f64) -> Self;
+    fn area(&self) -> f64;
+}
+
+#[mod_template::define(
+    macro_rules! define_shape_checks;
+    constructions(SHAPE -> TheShape),
+    types(TheShape: crate::Shape),
+    consts(MIN_AREA: f64),
+)]
+mod __ {
+    // FIXME: `cargo test` thinks this is unused. See test `2_1` for details.
+    #[allow(unused_imports)]
+    use crate::Shape;
+
+    #[__CONSTRUCT(shape as SHAPE)]
+    pub fn check_area() {
+        assert!(shape.area() >= MIN_AREA);
+    }
+
+    pub fn check_growth() {
+        let shape = TheShape::new(1.0);
+        let larger = TheShape::new(2.0);
+        assert!(shape.area() < larger.area());
+    }
+}
+
+#[test]
+fn local_types() {
+    struct Square(f64);
+    impl Shape for Square {
+        fn new(size: f64) -> Self {
+            Self(size)
+        }
+        fn area(&self) -> f64 {
+            self.0 * self.0
+        }
+    }
+
+    const SIZE: f64 = 2.0;
+    fn make_square() -> Square {
+        Square::new(SIZE)
+    }
+
+    define_shape_checks! {
+        mod _;
+        constructions {
+            SHAPE => make_square(),
+        },
+        types {
+            TheShape => Square,
+        },
+        consts {
+            MIN_AREA => SIZE * SIZE,
+        },
+    }
+
+    check_area();
+    check_growth();
+}
+
+struct Circle(f64);
+impl Shape for Circle {
+    fn new(size: f64) -> Self {
+        Self(size)
+    }
+    fn area(&self) -> f64 {
+        3.0 * self.0 * self.0
+    }
+}
+
+#[test]
+fn named_instance() {
+    // NOTE: items in the block aren't visible from the module.
+    define_shape_checks! {
+        mod circle_checks;
+        constructions {
+            SHAPE => crate::Circle::new(1.0),
+        },
+        types {
+            TheShape => crate::Circle,
+        },
+        consts {
+            MIN_AREA => 3.0,
+        },
+    }
+
+    circle_checks::check_area();
+    circle_checks::check_growth();
+}
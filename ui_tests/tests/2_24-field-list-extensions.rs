@@ -0,0 +1,59 @@
+fn main() {}
+
+#[mod_template::define(
+    macro_rules! define_connection_mod;
+    attribute_substitutions(BACKEND?, ID?),
+)]
+mod __ {
+    #[__SUBSTITUTE(BACKEND)]
+    #[derive(Default)]
+    pub struct Connection {
+        pub name: String,
+        pub id: Id,
+    }
+
+    #[__SUBSTITUTE(ID)]
+    #[derive(Default)]
+    #[allow(dead_code)]
+    pub struct Id(pub u32);
+
+    #[allow(clippy::needless_update)]
+    pub fn connect(name: &str) -> Connection {
+        Connection {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+define_connection_mod! {
+    mod plain;
+}
+
+define_connection_mod! {
+    mod sqlite;
+    attribute_substitutions {
+        BACKEND => #[derive(Debug)] { pub path: String },
+        ID => #[derive(Debug)] { pub u64 },
+    },
+}
+
+#[test]
+fn plain() {
+    assert_eq!(plain::connect("plain").name, "plain");
+    assert_eq!(plain::connect("plain").id.0, 0);
+}
+
+#[test]
+fn sqlite() {
+    let connection = sqlite::Connection {
+        path: "db.sqlite".to_string(),
+        ..sqlite::connect("sqlite")
+    };
+    assert_eq!(connection.name, "sqlite");
+    assert_eq!(connection.path, "db.sqlite");
+    assert!(format!("{:?}", connection).contains("db.sqlite"));
+
+    let sqlite::Id(a, b) = connection.id;
+    assert_eq!((a, b), (0, 0));
+}
@@ -0,0 +1,84 @@
+fn main() {}
+
+trait Counter {
+    fn increment(&mut self);
+    fn count(&self) -> usize;
+}
+
+#[derive(Default)]
+struct SimpleCounter(usize);
+impl Counter for SimpleCounter {
+    fn increment(&mut self) {
+        self.0 += 1;
+    }
+    fn count(&self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Default)]
+struct SaturatingCounter(u8);
+impl Counter for SaturatingCounter {
+    fn increment(&mut self) {
+        self.0 = self.0.saturating_add(1);
+    }
+    fn count(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+#[mod_template::define(
+    macro_rules! define_counter_helpers;
+    constructions(COUNTER -> impl crate::Counter),
+)]
+mod __ {
+    #[allow(unused_imports)]
+    use crate::Counter;
+
+    #[__CONSTRUCT(mut counter as COUNTER)]
+    pub fn count_up_to(n: usize) -> usize {
+        for _ in 0..n {
+            counter.increment();
+        }
+        counter.count()
+    }
+
+    pub fn count_up_to_three() -> usize {
+        count_up_to(3)
+    }
+
+    pub struct Helper;
+    impl Helper {
+        #[__CONSTRUCT(mut counter as COUNTER)]
+        pub fn count_once(&self) -> usize {
+            counter.increment();
+            counter.count()
+        }
+
+        pub fn count_once_twice(&self) -> usize {
+            self.count_once() + self.count_once()
+        }
+    }
+}
+
+define_counter_helpers! {
+    mod simple_counter_helpers;
+    constructions {
+        COUNTER => crate::SimpleCounter::default(),
+    },
+}
+
+define_counter_helpers! {
+    mod saturating_counter_helpers;
+    constructions {
+        COUNTER => crate::SaturatingCounter(254),
+    },
+}
+
+#[test]
+fn it_works() {
+    assert_eq!(simple_counter_helpers::count_up_to_three(), 3);
+    assert_eq!(saturating_counter_helpers::count_up_to_three(), 255);
+    assert_eq!(simple_counter_helpers::Helper.count_once_twice(), 2);
+    assert_eq!(saturating_counter_helpers::Helper.count_once_twice(), 510);
+}
@@ -0,0 +1,86 @@
+fn main() {}
+
+#[mod_template::define(
+    macro_rules! define_buffer_test_suite;
+    consts(CAPACITY: usize, FILL: u8),
+)]
+mod __ {
+    static BUFFER: [u8; CAPACITY] = [FILL; CAPACITY];
+
+    struct Fixed<const N: usize>([u8; N]);
+
+    #[test]
+    fn it_works() {
+        assert_eq!(BUFFER.len(), CAPACITY);
+        assert!(BUFFER.iter().all(|x| *x == FILL));
+
+        let fixed = Fixed::<CAPACITY>([FILL; CAPACITY]);
+        assert_eq!(fixed.0, BUFFER);
+    }
+
+    mod sub {
+        #[test]
+        fn it_also_works() {
+            assert_eq!([0u8; CAPACITY].len(), CAPACITY);
+        }
+    }
+}
+
+define_buffer_test_suite! {
+    mod small_buffer_test_suite;
+    consts {
+        CAPACITY => 4,
+        FILL => 1,
+    },
+}
+
+define_buffer_test_suite! {
+    mod large_buffer_test_suite;
+    consts {
+        CAPACITY => 1024,
+        FILL => 0xFF,
+    },
+}
+
+pub struct Limits {
+    pub min: u32,
+    pub max: u32,
+}
+
+#[mod_template::define(
+    macro_rules! define_greeting_test_suite;
+    consts(
+        GREETING: &'static str,
+        LIMITS: crate::Limits,
+    ),
+    attribute_substitutions(TEST),
+)]
+mod __ {
+    #[__SUBSTITUTE(TEST)]
+    fn it_greets() {
+        assert!(GREETING.starts_with("Hello"));
+        assert!(LIMITS.min < LIMITS.max);
+    }
+}
+
+define_greeting_test_suite! {
+    mod wide_limits_greeting_test_suite;
+    consts {
+        GREETING => "Hello, world!",
+        LIMITS => crate::Limits { min: 0, max: 10 },
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+}
+
+define_greeting_test_suite! {
+    mod narrow_limits_greeting_test_suite;
+    consts {
+        GREETING => "Hello!",
+        LIMITS => crate::Limits { min: 1, max: 2 },
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+}
@@ -0,0 +1,64 @@
+fn main() {}
+
+#[mod_template::define(
+    macro_rules! define_answer_mod;
+    constructions(ANSWER -> i32),
+    attribute_substitutions(TEST),
+    idents(GET_ANSWER, TEST_ANSWER),
+)]
+mod __ {
+    #[__CONSTRUCT(answer as ANSWER)]
+    #[no_mangle]
+    #[__IDENT(GET_ANSWER)]
+    pub extern "C" fn get_answer() -> i32 {
+        answer
+    }
+
+    #[__SUBSTITUTE(TEST)]
+    #[__IDENT(TEST_ANSWER)]
+    fn test_answer() {
+        assert_eq!(__IDENT(GET_ANSWER)(), 42);
+        assert_eq!(other::GET_ANSWER(), 0);
+    }
+
+    mod other {
+        #[allow(non_snake_case)]
+        pub fn GET_ANSWER() -> i32 {
+            0
+        }
+    }
+}
+
+define_answer_mod! {
+    mod positive;
+    constructions {
+        ANSWER => 42,
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+    idents {
+        GET_ANSWER => get_positive_answer,
+        TEST_ANSWER => positive_answer_is_42,
+    },
+}
+
+define_answer_mod! {
+    mod negative;
+    constructions {
+        ANSWER => -(-42),
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+    idents {
+        GET_ANSWER => get_negative_answer,
+        TEST_ANSWER => negated_negative_answer_is_42,
+    },
+}
+
+#[test]
+fn exported_names() {
+    assert_eq!(positive::get_positive_answer(), 42);
+    assert_eq!(negative::get_negative_answer(), 42);
+}
@@ -0,0 +1,53 @@
+fn main() {}
+
+mod numbers {
+    pub fn double(n: i32) -> i32 {
+        n * 2
+    }
+}
+
+#[mod_template::define(
+    macro_rules! define_double_test_suite;
+    constructions(INPUT -> i32),
+    attribute_substitutions(TEST),
+)]
+mod __ {
+    use crate::numbers::double;
+
+    fn quadruple(n: i32) -> i32 {
+        double(double(n))
+    }
+
+    #[__CONSTRUCT(input as INPUT)]
+    #[__SUBSTITUTE(TEST)]
+    fn quadruple_is_double_of_double() {
+        assert_eq!(quadruple(input), double(input) * 2);
+    }
+}
+
+define_double_test_suite! {
+    mod positive;
+    constructions {
+        INPUT => 21,
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+    items {
+        #[test]
+        fn quadruple_of_21_is_84() {
+            assert_eq!(quadruple(21), 84);
+            assert_eq!(double(42), 84);
+        }
+    },
+}
+
+define_double_test_suite! {
+    mod negative;
+    constructions {
+        INPUT => -21,
+    },
+    attribute_substitutions {
+        TEST => #[test],
+    },
+}
@@ -0,0 +1,72 @@
+fn main() {}
+
+trait Store {
+    fn get(&self) -> i32;
+}
+
+trait TransactionalStore: Store {
+    fn get_in_transaction(&self) -> i32;
+}
+
+struct SimpleStore;
+impl Store for SimpleStore {
+    fn get(&self) -> i32 {
+        42
+    }
+}
+
+struct FancyStore;
+impl Store for FancyStore {
+    fn get(&self) -> i32 {
+        42
+    }
+}
+impl TransactionalStore for FancyStore {
+    fn get_in_transaction(&self) -> i32 {
+        42
+    }
+}
+
+#[mod_template::define(
+    macro_rules! define_store_test_suite;
+    types(TheStore),
+    constructions(STORE -> TheStore),
+    attribute_substitutions(TEST),
+)]
+mod __ {
+    #[allow(unused_imports)]
+    use crate::{Store, TransactionalStore};
+
+    #[__CONSTRUCT(store as STORE)]
+    #[__SUBSTITUTE(TEST)]
+    fn it_works()
+    where
+        TheStore: Store,
+    {
+        assert_eq!(store.get(), 42);
+    }
+
+    #[__CONSTRUCT(store as STORE)]
+    #[__SUBSTITUTE(TEST)]
+    fn it_works_in_transaction()
+    where
+        TheStore: TransactionalStore,
+    {
+        assert_eq!(store.get_in_transaction(), 42);
+    }
+}
+
+define_store_test_suite! {
+    mod simple_store_test_suite;
+    types { TheStore => crate::SimpleStore },
+    constructions { STORE => crate::SimpleStore },
+    attribute_substitutions { TEST => #[test] },
+    skip { it_works_in_transaction },
+}
+
+define_store_test_suite! {
+    mod fancy_store_test_suite;
+    types { TheStore => crate::FancyStore },
+    constructions { STORE => crate::FancyStore },
+    attribute_substitutions { TEST => #[test] },
+}
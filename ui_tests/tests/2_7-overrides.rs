@@ -0,0 +1,58 @@
+fn main() {}
+
+trait Greeter {
+    fn greet(&self, name: &str) -> String;
+}
+
+struct EnglishGreeter;
+impl Greeter for EnglishGreeter {
+    fn greet(&self, name: &str) -> String {
+        format!("Hello, {}!", name)
+    }
+}
+
+struct FrenchGreeter;
+impl Greeter for FrenchGreeter {
+    fn greet(&self, name: &str) -> String {
+        format!("Bonjour, {} !", name)
+    }
+}
+
+#[mod_template::define(
+    macro_rules! define_greeter_test_suite;
+    constructions(GREETER -> Box<dyn crate::Greeter>),
+    attribute_substitutions(TEST),
+)]
+mod __ {
+    #[allow(unused_imports)]
+    use crate::Greeter;
+
+    #[__CONSTRUCT(greeter as GREETER)]
+    #[__SUBSTITUTE(TEST)]
+    fn it_mentions_the_name() {
+        assert!(greeter.greet("Rust").contains("Rust"));
+    }
+
+    #[__CONSTRUCT(greeter as GREETER)]
+    #[__SUBSTITUTE(TEST)]
+    fn it_greets() {
+        assert_eq!(greeter.greet("Rust"), "Hello, Rust!");
+    }
+}
+
+define_greeter_test_suite! {
+    mod english_greeter_test_suite;
+    constructions { GREETER => Box::new(crate::EnglishGreeter) },
+    attribute_substitutions { TEST => #[test] },
+}
+
+define_greeter_test_suite! {
+    mod french_greeter_test_suite;
+    constructions { GREETER => Box::new(crate::FrenchGreeter) },
+    attribute_substitutions { TEST => #[test] },
+    overrides {
+        fn it_greets() {
+            assert_eq!(greeter.greet("Rust"), "Bonjour, Rust !");
+        }
+    },
+}
@@ -0,0 +1,66 @@
+fn main() {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Clock {
+    now: u64,
+}
+
+impl Clock {
+    fn system() -> Self {
+        Self { now: 1_700_000_000 }
+    }
+
+    fn fixed(now: u64) -> Self {
+        Self { now }
+    }
+}
+
+async fn started_at() -> u64 {
+    0
+}
+
+#[mod_template::define(
+    macro_rules! define_clock_test_suite;
+    constructions(
+        CLOCK -> crate::Clock = crate::Clock::system(),
+        EXPECTED_NOW -> u64,
+        STARTED_AT -> u64 = crate::started_at().await,
+    ),
+    attribute_substitutions(TEST, ASYNC_TEST),
+)]
+mod __ {
+    #[__CONSTRUCT(clock as CLOCK, expected_now as EXPECTED_NOW)]
+    #[__SUBSTITUTE(TEST)]
+    fn it_works() {
+        assert_eq!(clock.now, expected_now);
+    }
+
+    #[__CONSTRUCT(started_at as STARTED_AT, expected_now as EXPECTED_NOW)]
+    #[__SUBSTITUTE(ASYNC_TEST)]
+    async fn it_works_asynchronously() {
+        assert!(started_at <= expected_now);
+    }
+}
+
+define_clock_test_suite! {
+    mod system_clock_test_suite;
+    constructions {
+        EXPECTED_NOW => 1_700_000_000,
+    },
+    attribute_substitutions {
+        TEST => #[test],
+        ASYNC_TEST => #[tokio::test],
+    },
+}
+
+define_clock_test_suite! {
+    mod fixed_clock_test_suite;
+    constructions {
+        CLOCK => crate::Clock::fixed(42),
+        EXPECTED_NOW => 42,
+    },
+    attribute_substitutions {
+        TEST => #[test],
+        ASYNC_TEST => #[tokio::test],
+    },
+}
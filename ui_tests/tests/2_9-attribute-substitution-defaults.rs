@@ -0,0 +1,68 @@
+fn main() {}
+
+#[mod_template::define(
+    macro_rules! define_parity_test_suite;
+    constructions(NUMBER -> i32),
+    attribute_substitutions(TEST = #[test]),
+)]
+mod __ {
+    #[__CONSTRUCT(number as NUMBER)]
+    #[__SUBSTITUTE(TEST)]
+    fn it_is_even() {
+        assert_eq!(number % 2, 0);
+    }
+}
+
+define_parity_test_suite! {
+    mod two;
+    constructions {
+        NUMBER => 2,
+    },
+}
+
+define_parity_test_suite! {
+    mod four;
+    constructions {
+        NUMBER => 4,
+    },
+    attribute_substitutions {
+        TEST => #[::core::prelude::v1::test],
+    },
+}
+
+define_parity_test_suite! {
+    mod three;
+    constructions {
+        NUMBER => 3,
+    },
+    attribute_substitutions {
+        TEST => #[test] #[ignore = "three is odd"],
+    },
+}
+
+#[mod_template::define(
+    macro_rules! define_traced_mod;
+    attribute_substitutions(TRACE?),
+)]
+mod __ {
+    #[__SUBSTITUTE(TRACE)]
+    pub fn answer() -> i32 {
+        42
+    }
+}
+
+define_traced_mod! {
+    mod untraced;
+}
+
+define_traced_mod! {
+    mod traced;
+    attribute_substitutions {
+        TRACE => #[inline(never)],
+    },
+}
+
+#[test]
+fn optional_attribute_substitutions() {
+    assert_eq!(untraced::answer(), traced::answer());
+}
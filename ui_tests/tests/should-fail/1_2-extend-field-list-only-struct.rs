@@ -0,0 +1,12 @@
+use mod_template::extend_field_list;
+
+fn main() {}
+
+#[extend_field_list(.., a: u8)]
+fn an_fn() {}
+
+#[extend_field_list(.., a: u8)]
+struct AUnitStruct;
+
+#[extend_field_list(.., u8)]
+struct ANamedStruct {}
@@ -0,0 +1,26 @@
+fn main() {}
+
+#[mod_template::define(
+    macro_rules! define_foo;
+    attribute_substitutions(FOO(.., n: i32)),
+)]
+mod __ {
+    #[__SUBSTITUTE(FOO)]
+    fn foo() -> i32 {
+        n
+    }
+}
+
+define_foo! {
+    mod good;
+    attribute_substitutions {
+        FOO => (.., n: i32, _m: i32),
+    },
+}
+
+define_foo! {
+    mod bad;
+    attribute_substitutions {
+        FOO => (.., _m: i32, n: i32),
+    },
+}
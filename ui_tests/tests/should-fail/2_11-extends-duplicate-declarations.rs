@@ -0,0 +1,21 @@
+fn main() {}
+
+#[mod_template::define(
+    macro_rules! define_base_test_suite;
+    constructions(FOO -> i32),
+)]
+mod __ {}
+
+#[mod_template::define(
+    macro_rules! define_derived_test_suite;
+    extends(define_base_test_suite),
+    constructions(FOO -> i64),
+)]
+mod __ {}
+
+define_derived_test_suite! {
+    mod a_test_suite;
+    constructions {
+        FOO => 42,
+    },
+}
@@ -0,0 +1,31 @@
+fn main() {}
+
+#[mod_template::define(
+    macro_rules! define_inner_test_suite;
+    constructions(FOO -> i32),
+)]
+mod __ {}
+
+define_inner_test_suite! {
+    mod an_inner_test_suite;
+    constructions {
+        FOO => 42,
+    },
+}
+
+#[mod_template::define(
+    macro_rules! define_outer_test_suite;
+    constructions(BAR -> i32),
+)]
+mod __ {
+    #[__NESTED]
+    define_inner_test_suite! {
+        mod inner;
+        constructions {
+            FOO => __FORWARD(BAZ),
+        },
+        items {
+            const QUX: i32 = __FORWARD(BAR);
+        },
+    }
+}
@@ -0,0 +1,31 @@
+fn main() {}
+
+#[mod_template::define(
+    macro_rules! define_test_suite;
+)]
+mod __ {
+    #[__FROM_FILE]
+    mod missing {}
+
+    #[__FROM_FILE("missing.rs")]
+    mod also_missing {}
+
+    #[__FROM_FILE]
+    mod not_empty {
+        fn f() {}
+    }
+}
+
+#[mod_template::define(
+    macro_rules! define_another_test_suite;
+    path = "missing.rs",
+)]
+mod __ {}
+
+#[mod_template::define(
+    macro_rules! define_yet_another_test_suite;
+    path = "2_13-bad-files.rs",
+)]
+mod __ {
+    fn f() {}
+}
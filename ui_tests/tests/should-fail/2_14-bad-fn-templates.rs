@@ -0,0 +1,31 @@
+fn main() {}
+
+#[mod_template::define(
+    macro_rules! define_sized_test;
+    types(Foo),
+)]
+fn __(foo: Foo) {}
+
+#[mod_template::define(
+    macro_rules! define_test;
+    attribute_substitutions(TEST = #[test]),
+)]
+#[__SUBSTITUTE(TEST)]
+fn __() {}
+
+define_test! {
+    mod not_a_fn;
+}
+
+define_test! {
+    fn with_items;
+    items {
+        fn f() {}
+    }
+}
+
+#[mod_template::define(
+    macro_rules! define_derived_test;
+    extends(define_test),
+)]
+fn __() {}
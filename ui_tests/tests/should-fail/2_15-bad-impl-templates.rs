@@ -0,0 +1,55 @@
+fn main() {}
+
+struct Foo;
+
+#[mod_template::define(
+    macro_rules! impl_foo;
+)]
+impl Foo {}
+
+#[mod_template::define(
+    macro_rules! impl_nothing;
+    consts(N: usize),
+)]
+impl __ {
+    const N2: usize = N * 2;
+}
+
+#[mod_template::define(
+    macro_rules! impl_name;
+)]
+impl __ {
+    #[allow(dead_code)]
+    fn name() -> &'static str {
+        "name"
+    }
+}
+
+impl_name! {
+    mod not_an_impl;
+}
+
+impl_name! {
+    pub impl crate::Foo;
+}
+
+#[mod_template::define(
+    macro_rules! impl_field_reader;
+)]
+impl __ {
+    #[allow(dead_code)]
+    fn first(&self) -> u8 {
+        (*self).0
+    }
+}
+
+#[mod_template::define(
+    macro_rules! impl_typed_getter;
+    types(Value: Copy),
+)]
+impl __ {
+    #[allow(dead_code)]
+    fn value(&self) -> Value {
+        unimplemented!()
+    }
+}
@@ -0,0 +1,37 @@
+fn main() {}
+
+#[mod_template::define(
+    macro_rules! define_fns;
+)]
+mod __ {
+    #[allow(dead_code)]
+    fn an_fn() {}
+}
+
+define_fns! {
+    mod _;
+}
+
+define_fns! {
+    mod _;
+}
+
+define_fns! {
+    pub mod _;
+}
+
+#[mod_template::define(
+    macro_rules! define_buffer;
+    consts(CAPACITY: usize),
+)]
+mod __ {
+    #[allow(dead_code)]
+    static BUFFER: [u8; CAPACITY] = [0; CAPACITY];
+}
+
+define_buffer! {
+    mod _;
+    consts {
+        CAPACITY => 4,
+    },
+}
@@ -0,0 +1,38 @@
+fn main() {}
+
+#[mod_template::define(
+    macro_rules! define_size_checks;
+    constructions(SIZE -> f64),
+)]
+mod __ {
+    #[__CONSTRUCT(size as SIZE)]
+    #[allow(dead_code)]
+    pub fn check_size() {
+        assert!(size > 0.0);
+    }
+}
+
+#[allow(dead_code)]
+fn local_variables() {
+    let size = 1.0;
+    assert!(size > 0.0);
+
+    define_size_checks! {
+        mod _;
+        constructions {
+            SIZE => size,
+        },
+    }
+}
+
+#[allow(dead_code)]
+fn named_instance() {
+    const LEN: f64 = 1.0;
+
+    define_size_checks! {
+        mod size_checks;
+        constructions {
+            SIZE => LEN,
+        },
+    }
+}
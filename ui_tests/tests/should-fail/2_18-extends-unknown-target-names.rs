@@ -0,0 +1,21 @@
+fn main() {}
+
+#[mod_template::define(
+    macro_rules! define_base_test_suite;
+    constructions(STORE -> Vec<i32>),
+    attribute_substitutions(TEST),
+)]
+mod __ {}
+
+#[mod_template::define(
+    #[allow(unused_macros)]
+    macro_rules! define_derived_test_suite;
+    extends(define_base_test_suite),
+)]
+mod __ {
+    #[__CONSTRUCT(store as STOER)]
+    #[__SUBSTITUTE(TEST)]
+    fn it_works() {
+        assert!(store.is_empty());
+    }
+}
@@ -0,0 +1,20 @@
+fn main() {}
+
+#[mod_template::define(
+    macro_rules! define_display_helpers;
+    constructions(VALUE -> impl std::fmt::Display),
+)]
+mod __ {
+    #[__CONSTRUCT(value as VALUE)]
+    pub fn displayed() -> impl std::fmt::Display {
+        value
+    }
+
+    pub struct Wrapper;
+    impl std::fmt::Display for Wrapper {
+        #[__CONSTRUCT(value as VALUE)]
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}", value)
+        }
+    }
+}
@@ -0,0 +1,13 @@
+fn main() {}
+
+#[mod_template::define(
+    macro_rules! define_foo;
+    constructions(FOO -> i32),
+    types(Bar: Copy),
+    consts(BAZ: usize),
+    idents(FOO, Bar, BAZ, QUX),
+)]
+mod __ {
+    #[__IDENT(QUX)]
+    fn qux() {}
+}
@@ -0,0 +1,37 @@
+fn main() {}
+
+struct Foo;
+struct Bar<U>(U);
+
+#[mod_template::define(
+    macro_rules! impl_size;
+    consts(SIZE: usize),
+)]
+impl __ {
+    #[allow(dead_code)]
+    fn size() -> usize {
+        SIZE
+    }
+}
+
+impl_size! {
+    impl crate::Foo;
+    matrix {
+        size {
+            one => { consts { SIZE => 1 } },
+            two => { consts { SIZE => 2 } },
+        },
+    },
+}
+
+impl_size! {
+    mods {
+        impl<U> crate::Bar<U> where U: Copy => {
+            matrix {
+                size {
+                    one => { consts { SIZE => 1 } },
+                },
+            },
+        },
+    },
+}
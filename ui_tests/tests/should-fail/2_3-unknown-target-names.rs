@@ -19,4 +19,8 @@ mod __ {
 
     #[__SUBSTITUTE(CONS)]
     fn bad_use_construction_as_attribute_substitution() {}
+
+    fn bad_ident_not_found() {
+        __IDENT(NOT_FOUND)();
+    }
 }
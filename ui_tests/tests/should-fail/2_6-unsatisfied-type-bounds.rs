@@ -0,0 +1,21 @@
+fn main() {}
+
+trait Shape {}
+
+struct Square;
+impl Shape for Square {}
+
+struct NotAShape;
+
+#[mod_template::define(macro_rules! define_foo; types(TheShape: crate::Shape))]
+mod __ {}
+
+define_foo! {
+    mod good;
+    types { TheShape => crate::Square },
+}
+
+define_foo! {
+    mod bad;
+    types { TheShape => crate::NotAShape },
+}
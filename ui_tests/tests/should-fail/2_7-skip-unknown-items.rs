@@ -0,0 +1,16 @@
+fn main() {}
+
+#[mod_template::define(macro_rules! define_foo;)]
+mod __ {
+    fn foo() {}
+}
+
+define_foo! {
+    mod good;
+    skip { foo },
+}
+
+define_foo! {
+    mod bad;
+    skip { foo, bar, baz },
+}
@@ -0,0 +1,64 @@
+fn main() {}
+
+#[mod_template::define(macro_rules! define_foo;)]
+mod __ {
+    fn foo() -> i32 {
+        1
+    }
+
+    fn bar(x: i32) -> i32 {
+        x
+    }
+
+    struct Baz;
+    impl Baz {
+        #[allow(dead_code)]
+        fn baz() {}
+    }
+}
+
+define_foo! {
+    mod good;
+    overrides {
+        fn foo() -> i32 {
+            2
+        }
+
+        fn bar(x: i32,) -> i32 {
+            x + 1
+        }
+    },
+}
+
+define_foo! {
+    mod bad;
+    overrides {
+        fn foo() -> u32 {
+            2
+        }
+
+        fn baz() {}
+
+        fn bar(x: (i32,)) -> i32 {
+            x.0
+        }
+
+        #[inline]
+        fn foo() -> i32 {
+            2
+        }
+    },
+}
+
+define_foo! {
+    mod duplicate;
+    overrides {
+        fn foo() -> i32 {
+            2
+        }
+
+        fn foo() -> i32 {
+            3
+        }
+    },
+}
@@ -0,0 +1,12 @@
+fn main() {}
+
+#[mod_template::define(
+    macro_rules! define_foo;
+    constructions(DEFAULTED -> i32 = 42, REQUIRED -> i32),
+)]
+mod __ {}
+
+define_foo! {
+    mod missing_required;
+    constructions {},
+}